@@ -34,7 +34,7 @@ use std::collections::HashMap;
 use std::fmt;
 use std::mem;
 use std::sync::Arc;
-use style::computed_values::{border_style, filter, image_rendering, mix_blend_mode};
+use style::computed_values::{border_style, image_rendering, mix_blend_mode};
 use style_traits::cursor::Cursor;
 use text::TextRun;
 use text::glyph::ByteIndex;
@@ -73,6 +73,268 @@ pub struct LayerInfo {
     pub background_color: Color,
 }
 
+/// Uniquely identifies a node in the scroll root tree. Scroll roots are numbered starting from
+/// the root, which is always `ScrollRootId::root()`.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct ScrollRootId(pub usize);
+
+impl ScrollRootId {
+    /// Returns the ID of the topmost scroll root, which always exists and never scrolls.
+    #[inline]
+    pub fn root() -> ScrollRootId {
+        ScrollRootId(0)
+    }
+}
+
+/// Whether a scroll root responds to scroll offset along one axis. `overflow-x: scroll` and
+/// `overflow-y: scroll` can be set independently in CSS, so a scroll root needs to record
+/// sensitivity per axis rather than as a single all-or-nothing policy.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub enum AxisScrollSensitivity {
+    /// This axis scrolls: accumulated offset along it is applied during hit testing and
+    /// clipping.
+    Sensitive,
+    /// This axis does not scroll: any offset recorded for it is ignored, and the clip rect on
+    /// this axis is clamped to the content box instead of the (possibly smaller) overflow area.
+    Insensitive,
+}
+
+/// Per-axis scroll sensitivity for a scroll root.
+#[derive(Clone, Copy, Eq, PartialEq, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct ScrollSensitivity {
+    pub x: AxisScrollSensitivity,
+    pub y: AxisScrollSensitivity,
+}
+
+impl ScrollSensitivity {
+    /// Scrolls freely along both axes, the common case for `overflow: scroll`/`auto`.
+    pub fn both() -> ScrollSensitivity {
+        ScrollSensitivity {
+            x: AxisScrollSensitivity::Sensitive,
+            y: AxisScrollSensitivity::Sensitive,
+        }
+    }
+
+    /// Scrolls along neither axis.
+    pub fn neither() -> ScrollSensitivity {
+        ScrollSensitivity {
+            x: AxisScrollSensitivity::Insensitive,
+            y: AxisScrollSensitivity::Insensitive,
+        }
+    }
+}
+
+/// A node in the scroll root tree. Unlike a `StackingContext`, a `ScrollRoot` exists purely to
+/// describe "what scrolls" -- it has no bearing on paint order. Stacking contexts that establish
+/// a scrollable overflow area point at one of these via `StackingContext::established_scroll_root`,
+/// and every `BaseDisplayItem` records which scroll root it should be offset by.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct ScrollRoot {
+    /// The unique ID of this scroll root.
+    pub id: ScrollRootId,
+
+    /// The ID of the parent scroll root, if any. Scroll offsets accumulate up this chain.
+    pub parent_id: Option<ScrollRootId>,
+
+    /// The clipping rectangle for this scroll root, in the coordinate system of its parent.
+    pub clip: Rect<Au>,
+
+    /// The size of the scrollable content, used by the compositor to clamp scroll offsets.
+    pub content_size: Size2D<Au>,
+
+    /// The scroll policy of this scroll root. A root with `ScrollPolicy::FixedPosition` acts as
+    /// a boundary: offset accumulation stops here, since fixed-position content underneath it is
+    /// positioned relative to the viewport rather than to any ancestor scroll offset.
+    pub scroll_policy: ScrollPolicy,
+
+    /// Which axes this scroll root actually scrolls along, so that `overflow-x: scroll` and
+    /// `overflow-y: scroll` can be expressed independently.
+    pub sensitivity: ScrollSensitivity,
+}
+
+impl ScrollRoot {
+    fn is_fixed_boundary(&self) -> bool {
+        self.scroll_policy == ScrollPolicy::FixedPosition
+    }
+
+    /// The clip rect to use while painting this scroll root's contents: the overflow clip on
+    /// any insensitive axis is widened to the full content box, since that axis never scrolls
+    /// and shouldn't cut content off that a scrolling viewport would otherwise reveal.
+    pub fn clip_rect_for_painting(&self) -> Rect<Au> {
+        let mut rect = self.clip;
+        if self.sensitivity.x == AxisScrollSensitivity::Insensitive {
+            rect.size.width = cmp::max(rect.size.width, self.content_size.width);
+        }
+        if self.sensitivity.y == AxisScrollSensitivity::Insensitive {
+            rect.size.height = cmp::max(rect.size.height, self.content_size.height);
+        }
+        rect
+    }
+}
+
+/// Uniquely identifies a clip node in the clip-scroll tree.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct ClipId(pub usize);
+
+impl ClipId {
+    /// The clip node that clips nothing, used by display items with no specific rounded-clip
+    /// ancestor of their own.
+    #[inline]
+    pub fn none() -> ClipId {
+        ClipId(0)
+    }
+}
+
+/// A node in the clip-scroll tree that only clips -- it has no scroll offset of its own. A
+/// rounded container clipping many children is stored once here and referenced by each of those
+/// children's `ClipAndScrollInfo`, instead of every one of them carrying its own fully-intersected
+/// `ClippingRegion` inline.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct ClipNode {
+    /// The unique ID of this clip node.
+    pub id: ClipId,
+
+    /// The ID of the parent clip node, if any. Regions accumulate up this chain.
+    pub parent: Option<ClipId>,
+
+    /// This node's own clipping region, in the coordinate system of its parent.
+    pub clip: ClippingRegion,
+}
+
+/// Identifies the clip-scroll tree nodes that apply to a display item: the scroll node whose
+/// offset should be applied during hit testing, and the clip node whose region (and whose
+/// ancestors' regions) should be intersected during painting and clip-based hit testing. Carrying
+/// this pair instead of an inline `ClippingRegion` lets several items that share a clip (or a
+/// scroll root) point at the same tree nodes rather than duplicating the data.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct ClipAndScrollInfo {
+    pub scroll_node_id: ScrollRootId,
+    pub clip_node_id: ClipId,
+}
+
+impl ClipAndScrollInfo {
+    /// A `ClipAndScrollInfo` for an item that scrolls with `scroll_node_id` and isn't clipped by
+    /// anything beyond what that scroll root itself clips to.
+    pub fn simple(scroll_node_id: ScrollRootId) -> ClipAndScrollInfo {
+        ClipAndScrollInfo {
+            scroll_node_id: scroll_node_id,
+            clip_node_id: ClipId::none(),
+        }
+    }
+}
+
+/// The tree of scroll and clip nodes for a `DisplayList`, built up as the display list is
+/// constructed. This is what hit testing and clipping consult instead of deriving scroll behavior
+/// from `StackingContextId` or carrying a fully-resolved `ClippingRegion` on every item.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct ClipScrollTree {
+    roots: HashMap<ScrollRootId, ScrollRoot>,
+    clip_nodes: HashMap<ClipId, ClipNode>,
+    next_clip_id: usize,
+}
+
+impl ClipScrollTree {
+    pub fn new() -> ClipScrollTree {
+        let mut roots = HashMap::new();
+        roots.insert(ScrollRootId::root(), ScrollRoot {
+            id: ScrollRootId::root(),
+            parent_id: None,
+            clip: max_rect(),
+            content_size: Size2D::zero(),
+            scroll_policy: ScrollPolicy::Scrollable,
+            sensitivity: ScrollSensitivity::both(),
+        });
+
+        let mut clip_nodes = HashMap::new();
+        clip_nodes.insert(ClipId::none(), ClipNode {
+            id: ClipId::none(),
+            parent: None,
+            clip: ClippingRegion::max(),
+        });
+
+        ClipScrollTree {
+            roots: roots,
+            clip_nodes: clip_nodes,
+            next_clip_id: 1,
+        }
+    }
+
+    pub fn add_scroll_root(&mut self, root: ScrollRoot) {
+        self.roots.insert(root.id, root);
+    }
+
+    pub fn get(&self, id: &ScrollRootId) -> Option<&ScrollRoot> {
+        self.roots.get(id)
+    }
+
+    /// Registers a new clip node with the given parent (or the "clips nothing" root if `None`)
+    /// and returns the ID it was assigned.
+    pub fn add_clip_node(&mut self, parent: Option<ClipId>, clip: ClippingRegion) -> ClipId {
+        let id = ClipId(self.next_clip_id);
+        self.next_clip_id += 1;
+        self.clip_nodes.insert(id, ClipNode {
+            id: id,
+            parent: Some(parent.unwrap_or_else(ClipId::none)),
+            clip: clip,
+        });
+        id
+    }
+
+    pub fn get_clip_node(&self, id: &ClipId) -> Option<&ClipNode> {
+        self.clip_nodes.get(id)
+    }
+
+    /// The effective clipping region for `id`: its own region intersected with every ancestor's,
+    /// walking the parent chain instead of requiring each display item to carry the fully
+    /// pre-intersected region.
+    pub fn resolved_clip(&self, id: ClipId) -> ClippingRegion {
+        let mut region = ClippingRegion::max();
+        let mut current = Some(id);
+        while let Some(current_id) = current {
+            let node = match self.clip_nodes.get(&current_id) {
+                Some(node) => node,
+                None => break,
+            };
+            region = region.intersect(&node.clip);
+            current = node.parent;
+        }
+        region
+    }
+
+    /// Walks from `id` up to the root, accumulating the scroll offset recorded for each node in
+    /// `scroll_offsets`. Accumulation stops as soon as a fixed-position boundary is reached, so
+    /// that fixed items are left to use the raw client point.
+    pub fn accumulated_scroll_offset_for_hit_testing(&self,
+                                                      id: ScrollRootId,
+                                                      scroll_offsets: &ScrollOffsetMap)
+                                                      -> Point2D<Au> {
+        let mut offset = Point2D::zero();
+        let mut current = Some(id);
+        while let Some(current_id) = current {
+            let root = match self.roots.get(&current_id) {
+                Some(root) => root,
+                None => break,
+            };
+
+            if let Some(scroll_offset) = scroll_offsets.get(&current_id) {
+                if root.sensitivity.x == AxisScrollSensitivity::Sensitive {
+                    offset.x = offset.x + Au::from_f32_px(scroll_offset.x);
+                }
+                if root.sensitivity.y == AxisScrollSensitivity::Sensitive {
+                    offset.y = offset.y + Au::from_f32_px(scroll_offset.y);
+                }
+            }
+
+            if root.is_fixed_boundary() {
+                break;
+            }
+
+            current = root.parent_id;
+        }
+        offset
+    }
+}
+
 impl LayerInfo {
     pub fn new(id: LayerId,
                scroll_policy: ScrollPolicy,
@@ -89,13 +351,178 @@ impl LayerInfo {
     }
 }
 
+/// The contents of a layer synthesized to preserve paint order, sized to the union of the
+/// `BaseDisplayItem` bounds it contains rather than to the bounds of the parent stacking context
+/// that triggered its creation. `items` are stored pre-translated so that they are relative to
+/// `origin`, which is itself relative to the parent stacking context's origin.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct PaintLayerContents {
+    /// The id of the layer these items should be painted into.
+    pub layer_id: LayerId,
+
+    /// This layer's origin, relative to the parent stacking context's origin.
+    pub origin: Point2D<Au>,
+
+    /// The items belonging to this layer, already translated so that they are relative to
+    /// `origin` instead of the parent stacking context's origin.
+    pub items: Vec<DisplayItem>,
+}
+
+impl PaintLayerContents {
+    /// Builds a `PaintLayerContents` from a flat run of display items, computing the layer's
+    /// bounds as the union of those items' bounds and repositioning the items relative to that
+    /// computed origin.
+    fn from_items(layer_id: LayerId, items: Vec<DisplayItem>) -> PaintLayerContents {
+        let bounds = items.iter().fold(None, |bounds: Option<Rect<Au>>, item| {
+            Some(match bounds {
+                Some(bounds) => bounds.union(&item.bounds()),
+                None => item.bounds(),
+            })
+        }).unwrap_or(Rect::zero());
+
+        let origin = bounds.origin;
+        let delta = Point2D::zero() - origin;
+        let items = items.into_iter().map(|item| item.translated(&delta)).collect();
+
+        PaintLayerContents {
+            layer_id: layer_id,
+            origin: origin,
+            items: items,
+        }
+    }
+
+    /// Draws this layer's items, translating by this layer's origin relative to the parent
+    /// stacking context instead of using the parent's (larger) bounds. Called by
+    /// `DisplayList::draw_synthesized_layer_into_context`, not directly.
+    pub fn draw_into_context(&self,
+                             paint_context: &mut PaintContext,
+                             transform: &Matrix4D<f32>,
+                             clip_scroll_tree: &ClipScrollTree) {
+        let transform = transform.pre_translated(self.origin.x.to_f32_px(),
+                                                 self.origin.y.to_f32_px(),
+                                                 0.0);
+        paint_context.draw_target.set_transform(&transform.to_2d());
+        for item in &self.items {
+            item.draw_into_context(paint_context, clip_scroll_tree);
+        }
+    }
+}
+
+/// A spatial acceleration structure built once per `DisplayList`, so that `new_partial` doesn't
+/// need to scan backwards through the list to find a stacking context's start and so that tiled
+/// painting can query the items relevant to a tile instead of walking (and bounds-testing) the
+/// entire list.
+#[derive(HeapSizeOf, Deserialize, Serialize)]
+struct DisplayListIndex {
+    /// The half-open `[start, end)` index range each stacking context occupies in `list`,
+    /// including its `PushStackingContext`/`PopStackingContext` boundary items.
+    stacking_context_ranges: HashMap<StackingContextId, (usize, usize)>,
+
+    /// Every item index in `list`, sorted by the minimum x-coordinate of the item's bounds. This
+    /// is the "interval" half of the acceleration structure: `items_intersecting` binary-searches
+    /// into this to find the first item that could possibly overlap a tile on the x axis, then
+    /// walks forward testing full intersection (including the y axis) only for candidates that
+    /// pass that first cheap test, instead of testing every item in the list.
+    items_sorted_by_min_x: Vec<usize>,
+
+    /// An upper bound on how far any item's bounds extend past its own minimum x-coordinate,
+    /// computed once here instead of on every `items_intersecting` query. This widens the binary
+    /// search window so that wide items starting to the left of a tile but overlapping it are
+    /// never missed.
+    max_item_width: Au,
+
+    /// The indices, in ascending (display-list) order, of every control item
+    /// (`PushStackingContext`/`PopStackingContext`/`PushReferenceFrame`/`PopReferenceFrame`) in
+    /// `list`. These carry no bounds to test against a tile, so they never show up as candidates
+    /// in `items_sorted_by_min_x`, but a tiled paint still needs every one of them to set up the
+    /// transforms, clips, and compositing state its surviving content items were painted under.
+    control_item_indices: Vec<usize>,
+}
+
+impl DisplayListIndex {
+    fn build(list: &[DisplayItem]) -> DisplayListIndex {
+        let mut stacking_context_ranges = HashMap::new();
+        let mut open_stacking_contexts = Vec::new();
+        let mut max_item_width = Au(0);
+        let mut control_item_indices = Vec::new();
+        for (index, item) in list.iter().enumerate() {
+            max_item_width = cmp::max(max_item_width, item.bounds().size.width);
+            if item.base().is_none() {
+                control_item_indices.push(index);
+            }
+            match *item {
+                DisplayItem::PushStackingContext(ref push) => {
+                    open_stacking_contexts.push((push.stacking_context.id, index));
+                }
+                DisplayItem::PopStackingContext(ref pop) => {
+                    let position = open_stacking_contexts.iter()
+                        .rposition(|&(id, _)| id == pop.stacking_context_id);
+                    if let Some(position) = position {
+                        let (id, start) = open_stacking_contexts.remove(position);
+                        stacking_context_ranges.insert(id, (start, index + 1));
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        let mut items_sorted_by_min_x: Vec<usize> = (0..list.len()).collect();
+        items_sorted_by_min_x.sort_by_key(|&index| list[index].bounds().origin.x);
+
+        DisplayListIndex {
+            stacking_context_ranges: stacking_context_ranges,
+            items_sorted_by_min_x: items_sorted_by_min_x,
+            max_item_width: max_item_width,
+            control_item_indices: control_item_indices,
+        }
+    }
+
+    fn range_for_stacking_context(&self, id: StackingContextId) -> Option<(usize, usize)> {
+        self.stacking_context_ranges.get(&id).cloned()
+    }
+
+    /// Returns the first position in `items_sorted_by_min_x` whose item could possibly intersect
+    /// a tile starting at `min_x`, found via binary search rather than a linear scan.
+    fn first_candidate_for_min_x(&self, list: &[DisplayItem], min_x: Au) -> usize {
+        let threshold = min_x - self.max_item_width;
+        let mut start = 0;
+        let mut end = self.items_sorted_by_min_x.len();
+        while start < end {
+            let mid = start + (end - start) / 2;
+            if list[self.items_sorted_by_min_x[mid]].bounds().origin.x < threshold {
+                start = mid + 1;
+            } else {
+                end = mid;
+            }
+        }
+        start
+    }
+}
+
 #[derive(HeapSizeOf, Deserialize, Serialize)]
 pub struct DisplayList {
     pub list: Vec<DisplayItem>,
+
+    /// The tree of scroll and clip nodes built up while this display list was constructed.
+    /// Scrolling and clip-rect computation during hit testing and painting consult this tree
+    /// instead of the stacking context tree.
+    pub clip_scroll_tree: ClipScrollTree,
+
+    /// Layers synthesized to preserve paint order when content from several stacking contexts
+    /// must be layerized. Each is sized to the union of its own items' bounds rather than to its
+    /// parent stacking context, keyed by the `LayerId` the compositor should create it under and
+    /// paint via `draw_synthesized_layer_into_context`, the same way it paints any other
+    /// compositor layer.
+    pub synthesized_layers: HashMap<LayerId, PaintLayerContents>,
+
+    /// Spatial acceleration structure over `list`, used by `new_partial` and
+    /// `items_intersecting`.
+    index: DisplayListIndex,
 }
 
 impl DisplayList {
     pub fn new(root_stacking_context: StackingContext,
+               clip_scroll_tree: ClipScrollTree,
                all_items: Vec<DisplayItem>)
                -> DisplayList {
         let mut mapped_items = HashMap::new();
@@ -105,15 +532,51 @@ impl DisplayList {
         }
 
         let mut list = Vec::new();
-        DisplayList::generate_display_list(&mut list, &mut mapped_items, root_stacking_context);
+        let mut synthesized_layer_items = HashMap::new();
+        DisplayList::generate_display_list(&mut list,
+                                           &mut mapped_items,
+                                           &mut synthesized_layer_items,
+                                           root_stacking_context);
+
+        let synthesized_layers = synthesized_layer_items.into_iter()
+            .map(|(layer_id, items)| (layer_id, PaintLayerContents::from_items(layer_id, items)))
+            .collect();
+
+        let list = DisplayList::cull_disjoint_items(list, &clip_scroll_tree);
+        let index = DisplayListIndex::build(&list);
 
         DisplayList {
             list: list,
+            clip_scroll_tree: clip_scroll_tree,
+            synthesized_layers: synthesized_layers,
+            index: index,
         }
     }
 
+    /// Returns every item whose bounds intersect `tile_rect`, plus every control item
+    /// (`PushStackingContext`/`PopStackingContext`/reference-frame items) needed to set up the
+    /// transforms, clips, and compositing state those items paint under, in display-list order.
+    /// Finding the content items this way avoids walking (and bounds-testing) the entire list for
+    /// every tile; the control items are cheap to merge in since they're already kept sorted.
+    pub fn items_intersecting(&self, tile_rect: &Rect<Au>) -> Vec<&DisplayItem> {
+        let start = self.index.first_candidate_for_min_x(&self.list, tile_rect.origin.x);
+        let max_x = tile_rect.max_x();
+
+        let mut indices: Vec<usize> = self.index.items_sorted_by_min_x[start..].iter()
+            .cloned()
+            .take_while(|&index| self.list[index].bounds().origin.x <= max_x)
+            .filter(|&index| self.list[index].bounds().intersects(tile_rect))
+            .chain(self.index.control_item_indices.iter().cloned())
+            .collect();
+        indices.sort();
+        indices.dedup();
+
+        indices.into_iter().map(|index| &self.list[index]).collect()
+    }
+
     fn generate_display_list(list: &mut Vec<DisplayItem>,
                              mapped_items: &mut HashMap<StackingContextId, Vec<DisplayItem>>,
+                             synthesized_layers: &mut HashMap<LayerId, Vec<DisplayItem>>,
                              mut stacking_context: StackingContext) {
         let mut child_stacking_contexts =
             mem::replace(&mut stacking_context.children, Vec::new());
@@ -122,14 +585,40 @@ impl DisplayList {
 
         let mut child_items = mapped_items.remove(&stacking_context.id)
                                           .unwrap_or(Vec::new());
-        child_items.sort_by(|a, b| a.base().section.cmp(&b.base().section));
+        child_items.sort_by(|a, b| a.section().cmp(&b.section()));
         child_items.reverse();
 
         let stacking_context_id = stacking_context.id;
         let real_stacking_context = stacking_context.context_type == StackingContextType::Real;
+
+        // If this stacking context's layer needs a companion layer to render above unlayered
+        // content (see `LayerInfo::next_layer_id`), route everything that would normally land in
+        // `list` into that companion's own bucket instead of the parent's `list`. That way
+        // `PaintLayerContents::from_items` can size the synthesized layer to just what it
+        // contains instead of inheriting this stacking context's bounds.
+        let companion_layer_id = stacking_context.layer_info.and_then(|info| {
+            if info.next_layer_id != info.layer_id {
+                Some(info.next_layer_id)
+            } else {
+                None
+            }
+        });
+        let mut companion_target = Vec::new();
+        let list: &mut Vec<DisplayItem> = match companion_layer_id {
+            Some(_) => &mut companion_target,
+            None => list,
+        };
+
+        let established_reference_frame = stacking_context.established_reference_frame;
+
+        if let Some(reference_frame) = established_reference_frame {
+            list.push(DisplayItem::PushReferenceFrame(Box::new(PushReferenceFrameItem {
+                reference_frame: reference_frame,
+            })));
+        }
+
         if real_stacking_context {
             list.push(DisplayItem::PushStackingContext(Box::new(PushStackingContextItem {
-                base: BaseDisplayItem::empty(),
                 stacking_context: stacking_context,
             })));
         }
@@ -145,7 +634,7 @@ impl DisplayList {
         // Step 3: Positioned descendants with negative z-indices.
         while child_stacking_contexts.peek().map_or(false, |child| child.z_index < 0) {
             let context = child_stacking_contexts.next().unwrap();
-            DisplayList::generate_display_list(list, mapped_items, context);
+            DisplayList::generate_display_list(list, mapped_items, synthesized_layers, context);
         }
 
         // Step 4: Block backgrounds and borders.
@@ -158,7 +647,7 @@ impl DisplayList {
         while child_stacking_contexts.peek().map_or(false,
             |child| child.context_type == StackingContextType::PseudoFloat) {
             let context = child_stacking_contexts.next().unwrap();
-            DisplayList::generate_display_list(list, mapped_items, context);
+            DisplayList::generate_display_list(list, mapped_items, synthesized_layers, context);
         }
 
         // Step 6 & 7: Content and inlines that generate stacking contexts.
@@ -169,7 +658,7 @@ impl DisplayList {
 
         // Step 8 & 9: Positioned descendants with nonnegative, numeric z-indices.
         for child in child_stacking_contexts {
-            DisplayList::generate_display_list(list, mapped_items, child);
+            DisplayList::generate_display_list(list, mapped_items, synthesized_layers, child);
         }
 
         // Step 10: Outlines.
@@ -178,11 +667,48 @@ impl DisplayList {
         if real_stacking_context {
             list.push(DisplayItem::PopStackingContext(Box::new(
                 PopStackingContextItem {
-                    base: BaseDisplayItem::empty(),
                     stacking_context_id: stacking_context_id,
                 }
             )));
         }
+
+        if let Some(reference_frame) = established_reference_frame {
+            list.push(DisplayItem::PopReferenceFrame(Box::new(PopReferenceFrameItem {
+                reference_frame_id: reference_frame.id,
+            })));
+        }
+
+        if let Some(layer_id) = companion_layer_id {
+            synthesized_layers.entry(layer_id).or_insert_with(Vec::new).extend(companion_target);
+        }
+    }
+
+    /// Drops every item whose `bounds` don't intersect its own clip's bounding rect at all, and
+    /// records the intersection as `clipped_bounds` on the rest. Items are never visible outside
+    /// that intersection, so doing this once here means `intersects_rect_in_parent_context`,
+    /// `hit_test`, and `draw_into_context` don't each have to resolve the clip and re-intersect it
+    /// against `bounds` on every call (and, in the painting case, pushing a transient clip that
+    /// contains an item's bounds entirely is pointless to begin with). Control items have no
+    /// `BaseDisplayItem` to cull by and are always kept.
+    fn cull_disjoint_items(list: Vec<DisplayItem>,
+                           clip_scroll_tree: &ClipScrollTree)
+                           -> Vec<DisplayItem> {
+        list.into_iter().filter_map(|mut item| {
+            let clipped_bounds = match item.base() {
+                None => return Some(item),
+                Some(base) => {
+                    let clip = clip_scroll_tree.resolved_clip(base.clip_and_scroll_info.clip_node_id);
+                    base.bounds.intersection(&clip.bounding_rect())
+                }
+            };
+            match clipped_bounds {
+                Some(clipped_bounds) => {
+                    item.base_mut().unwrap().clipped_bounds = clipped_bounds;
+                    Some(item)
+                }
+                None => None,
+            }
+        }).collect()
     }
 
     /// Draws the DisplayList in order.
@@ -200,7 +726,8 @@ impl DisplayList {
                              paint_context,
                              transform,
                              &Point2D::zero(),
-                             None);
+                             None,
+                             &ClipRegion::max());
     }
 
     /// Draws a single DisplayItem into the given PaintContext.
@@ -212,17 +739,45 @@ impl DisplayList {
         paint_context.draw_target.set_transform(&transform.to_2d());
 
         let item = &self.list[index];
-        item.draw_into_context(paint_context);
+        item.draw_into_context(paint_context, &self.clip_scroll_tree);
 
         paint_context.draw_target.set_transform(&old_transform);
     }
 
+    /// Draws the contents of a layer synthesized to preserve paint order (see
+    /// `synthesized_layers`) into the given PaintContext. Like `draw_into_context` and
+    /// `draw_item_at_index_into_context`, this is an entry point for the compositor's paint task
+    /// rather than something called from within this module: that's where the decision of which
+    /// `LayerId`s need painting, and in what order relative to the rest of the layer tree, is
+    /// made. Returns `false` without drawing anything if `layer_id` doesn't name a layer this
+    /// display list synthesized.
+    pub fn draw_synthesized_layer_into_context(&self,
+                                               layer_id: LayerId,
+                                               paint_context: &mut PaintContext,
+                                               transform: &Matrix4D<f32>)
+                                               -> bool {
+        match self.synthesized_layers.get(&layer_id) {
+            Some(layer) => {
+                layer.draw_into_context(paint_context, transform, &self.clip_scroll_tree);
+                true
+            }
+            None => false,
+        }
+    }
+
     fn draw_with_state<'a>(&'a self,
                            traversal: &mut DisplayListTraversal,
                            paint_context: &mut PaintContext,
                            transform: &Matrix4D<f32>,
                            subpixel_offset: &Point2D<Au>,
-                           tile_rect: Option<Rect<Au>>) {
+                           tile_rect: Option<Rect<Au>>,
+                           clip_region: &ClipRegion) {
+        // The transform carried by the `PushReferenceFrameItem`, if any, that immediately
+        // preceded the next `PushStackingContext` we see. For a real stacking context,
+        // `generate_display_list` emits a reference frame's items as a pair wrapping the
+        // `PushStackingContextItem` it belongs to, so capturing it here and consuming it on the
+        // very next stacking context picks up that context's transform.
+        let mut reference_frame_transform = Matrix4D::identity();
         while let Some(item) = traversal.next() {
             match item {
                 &DisplayItem::PushStackingContext(ref stacking_context_item) => {
@@ -232,15 +787,30 @@ impl DisplayList {
                                                    context,
                                                    paint_context,
                                                    transform,
-                                                   subpixel_offset);
+                                                   subpixel_offset,
+                                                   clip_region,
+                                                   &reference_frame_transform);
                     } else {
                         traversal.skip_to_end_of_stacking_context(context.id);
                     }
+                    reference_frame_transform = Matrix4D::identity();
                 }
                 &DisplayItem::PopStackingContext(_) => return,
+                // Reference frame boundaries carry no paintable content and no bounds of their
+                // own to cull against; they exist to hand their transform to the stacking context
+                // push that follows.
+                &DisplayItem::PushReferenceFrame(ref item) => {
+                    reference_frame_transform = *item.reference_frame.transform.value();
+                }
+                // A reference frame not immediately followed by a real stacking-context push
+                // (`generate_display_list` still emits the pair around pseudo stacking contexts)
+                // must not leak its transform onto whatever real stacking context comes next.
+                &DisplayItem::PopReferenceFrame(_) => {
+                    reference_frame_transform = Matrix4D::identity();
+                }
                 _ => {
-                    if item.intersects_rect_in_parent_context(tile_rect) {
-                        item.draw_into_context(paint_context);
+                    if item.intersects_rect_in_parent_context(tile_rect, &self.clip_scroll_tree) {
+                        item.draw_into_context(paint_context, &self.clip_scroll_tree);
                     }
                 }
             }
@@ -252,12 +822,15 @@ impl DisplayList {
                              stacking_context: &StackingContext,
                              paint_context: &mut PaintContext,
                              transform: &Matrix4D<f32>,
-                             subpixel_offset: &Point2D<Au>) {
+                             subpixel_offset: &Point2D<Au>,
+                             enclosing_clip_region: &ClipRegion,
+                             reference_frame_transform: &Matrix4D<f32>) {
         debug_assert!(stacking_context.context_type == StackingContextType::Real);
 
         let draw_target = paint_context.get_or_create_temporary_draw_target(
             &stacking_context.filters,
-            stacking_context.blend_mode);
+            stacking_context.blend_mode,
+            *stacking_context.opacity.value());
 
         let old_transform = paint_context.draw_target.get_transform();
         let pixels_per_px = paint_context.screen_pixels_per_px();
@@ -276,7 +849,7 @@ impl DisplayList {
                     .pre_translated(pixel_snapped_origin.x as AzFloat,
                                     pixel_snapped_origin.y as AzFloat,
                                     0.0)
-                    .pre_mul(&stacking_context.transform);
+                    .pre_mul(reference_frame_transform);
 
                 if transform.is_identity_or_simple_translation() {
                     let pixel_snapped_origin = Point2D::new(Au::from_f32_px(pixel_snapped_origin.x),
@@ -305,26 +878,60 @@ impl DisplayList {
             };
 
         {
+            // If this stacking context establishes a scroll root, clip to that scroll root's
+            // clip rect in addition to its own overflow rect. The scroll root tree owns "what
+            // scrolls" independently of this stacking context's paint order. This stacking
+            // context's own clip region (which may carry corner radii) is then intersected with
+            // whatever clip region was already in effect, so nested rounded clips compose
+            // correctly instead of the innermost one winning outright.
+            let own_overflow = match stacking_context.established_scroll_root {
+                Some(scroll_root_id) => {
+                    match self.clip_scroll_tree.get(&scroll_root_id) {
+                        Some(scroll_root) => {
+                            stacking_context.overflow
+                                            .intersection(&scroll_root.clip_rect_for_painting())
+                                            .unwrap_or(Rect::zero())
+                        }
+                        None => stacking_context.overflow,
+                    }
+                }
+                None => stacking_context.overflow,
+            };
+            let own_clip_region = ClipRegion {
+                main: own_overflow,
+                radii: stacking_context.overflow_radii,
+                complex: Vec::new(),
+            };
+            let clip_region = enclosing_clip_region.intersect(&own_clip_region);
+
             let mut paint_subcontext = PaintContext {
                 draw_target: draw_target.clone(),
                 font_context: &mut *paint_context.font_context,
                 page_rect: paint_context.page_rect,
                 screen_rect: paint_context.screen_rect,
-                clip_rect: Some(stacking_context.overflow),
+                clip_rect: Some(clip_region.main),
                 transient_clip: None,
                 layer_kind: paint_context.layer_kind,
                 subpixel_offset: subpixel_offset,
             };
 
-            // Set up our clip rect and transform.
+            // Set up our clip rect and transform. If any region on the stack carries corner
+            // radii, install a rounded-rect mask instead of a plain rectangular clip so that
+            // `overflow: hidden`/`scroll` containers with `border-radius` clip their contents
+            // properly.
             paint_subcontext.draw_target.set_transform(&transform.to_2d());
-            paint_subcontext.push_clip_if_applicable();
+            if clip_region.has_rounded_corners() {
+                paint_subcontext.push_rounded_clip_region_if_applicable(&clip_region);
+            } else {
+                paint_subcontext.push_clip_if_applicable();
+            }
 
             self.draw_with_state(traversal,
                                  &mut paint_subcontext,
                                  &transform,
                                  &subpixel_offset,
-                                 Some(transformed_transform));
+                                 Some(transformed_transform),
+                                 &clip_region);
 
             paint_subcontext.remove_transient_clip_if_applicable();
             paint_subcontext.pop_clip_if_applicable();
@@ -332,7 +939,10 @@ impl DisplayList {
 
         draw_target.set_transform(&old_transform);
         paint_context.draw_temporary_draw_target_if_necessary(
-            &draw_target, &stacking_context.filters, stacking_context.blend_mode);
+            &draw_target,
+            &stacking_context.filters,
+            stacking_context.blend_mode,
+            *stacking_context.opacity.value());
     }
 
     // Return all nodes containing the point of interest, bottommost first, and
@@ -341,7 +951,7 @@ impl DisplayList {
                     translated_point: &Point2D<Au>,
                     client_point: &Point2D<Au>,
                     scroll_offsets: &ScrollOffsetMap)
-                    -> Vec<DisplayItemMetadata> {
+                    -> Vec<ItemTag> {
         let mut result = Vec::new();
         let mut traversal = DisplayListTraversal::new(self);
         self.hit_test_contents(&mut traversal,
@@ -352,12 +962,26 @@ impl DisplayList {
         result
     }
 
+    /// Returns the tag of the topmost item under `translated_point`, if any, so that click
+    /// routing and cursor updates can share a single traversal instead of each re-walking the
+    /// display list.
+    pub fn topmost_hit_test_tag(&self,
+                                translated_point: &Point2D<Au>,
+                                client_point: &Point2D<Au>,
+                                scroll_offsets: &ScrollOffsetMap)
+                                -> Option<ItemTag> {
+        self.hit_test(translated_point, client_point, scroll_offsets).pop()
+    }
+
     pub fn hit_test_contents<'a>(&self,
                                  traversal: &mut DisplayListTraversal<'a>,
                                  translated_point: &Point2D<Au>,
                                  client_point: &Point2D<Au>,
                                  scroll_offsets: &ScrollOffsetMap,
-                                 result: &mut Vec<DisplayItemMetadata>) {
+                                 result: &mut Vec<ItemTag>) {
+        // See the matching field in `draw_with_state`: the transform carried by the
+        // `PushReferenceFrameItem` that immediately preceded the next `PushStackingContext`.
+        let mut reference_frame_transform = Matrix4D::identity();
         while let Some(item) = traversal.next() {
             match item {
                 &DisplayItem::PushStackingContext(ref stacking_context_item) => {
@@ -366,12 +990,32 @@ impl DisplayList {
                                                    translated_point,
                                                    client_point,
                                                    scroll_offsets,
-                                                   result);
+                                                   result,
+                                                   &reference_frame_transform);
+                    reference_frame_transform = Matrix4D::identity();
                 }
                 &DisplayItem::PopStackingContext(_) => return,
+                // Reference frame boundaries carry no hittable content; hit testing instead
+                // converts the translated point itself, in `hit_test_stacking_context`.
+                &DisplayItem::PushReferenceFrame(ref item) => {
+                    reference_frame_transform = *item.reference_frame.transform.value();
+                }
+                // See the matching arm in `draw_with_state`: don't let this reference frame's
+                // transform leak onto a stacking context push that isn't actually nested inside
+                // it.
+                &DisplayItem::PopReferenceFrame(_) => {
+                    reference_frame_transform = Matrix4D::identity();
+                }
                 _ => {
-                    if let Some(meta) = item.hit_test(*translated_point) {
-                        result.push(meta);
+                    // Walk this item's scroll root up to the root of the scroll root tree,
+                    // accumulating scroll offsets along the way. This is independent of the
+                    // stacking context the item happens to live in.
+                    let base = item.base().expect("control items are matched explicitly above");
+                    let scroll_offset = self.clip_scroll_tree.accumulated_scroll_offset_for_hit_testing(
+                        base.clip_and_scroll_info.scroll_node_id, scroll_offsets);
+                    let point = *translated_point - scroll_offset;
+                    if let Some(tag) = item.hit_test(point, &self.clip_scroll_tree) {
+                        result.push(tag);
                     }
                 }
             }
@@ -384,37 +1028,170 @@ impl DisplayList {
                         translated_point: &Point2D<Au>,
                         client_point: &Point2D<Au>,
                         scroll_offsets: &ScrollOffsetMap,
-                        result: &mut Vec<DisplayItemMetadata>) {
+                        result: &mut Vec<ItemTag>,
+                        reference_frame_transform: &Matrix4D<f32>) {
         let is_fixed = stacking_context.layer_info.map_or(false,
             |info| info.scroll_policy == ScrollPolicy::FixedPosition);
 
         // Convert the parent translated point into stacking context local transform space if the
         // stacking context isn't fixed.  If it's fixed, we need to use the client point anyway.
         debug_assert!(stacking_context.context_type == StackingContextType::Real);
-        let mut translated_point = if is_fixed {
+        let translated_point = if is_fixed {
             *client_point
         } else {
             let point = *translated_point - stacking_context.bounds.origin;
-            let inv_transform = stacking_context.transform.inverse().unwrap();
+            let inv_transform = reference_frame_transform.inverse().unwrap();
             let frac_point = inv_transform.transform_point(&Point2D::new(point.x.to_f32_px(),
                                                                          point.y.to_f32_px()));
             Point2D::new(Au::from_f32_px(frac_point.x), Au::from_f32_px(frac_point.y))
         };
 
-        // Adjust the translated point to account for the scroll offset if
-        // necessary. This can only happen when WebRender is in use.
-        //
-        // We don't perform this adjustment on the root stacking context because
-        // the DOM-side code has already translated the point for us (e.g. in
-        // `Window::hit_test_query()`) by now.
-        if !is_fixed && stacking_context.id != StackingContextId::root() {
-            if let Some(scroll_offset) = scroll_offsets.get(&stacking_context.id) {
-                translated_point.x -= Au::from_f32_px(scroll_offset.x);
-                translated_point.y -= Au::from_f32_px(scroll_offset.y);
+        // Note: scroll offset accumulation is no longer done here. It happens per-item in
+        // `hit_test_contents`, by walking that item's scroll root in `clip_scroll_tree`, which
+        // correctly handles several scrollable areas nesting inside one stacking context.
+        self.hit_test_contents(traversal, &translated_point, client_point, scroll_offsets, result);
+    }
+
+    /// Converts this display list into a WebRender display list, for use by the WebRender
+    /// painting backend instead of walking the list against an Azure `PaintContext`. Each scroll
+    /// root in `clip_scroll_tree` that a stacking context establishes is defined as a WebRender
+    /// scroll frame as that stacking context is entered, so scroll offsets are resolved GPU-side
+    /// instead of by the CPU point translation that `hit_test_stacking_context` performs for the
+    /// Azure path. The clip nodes of `clip_scroll_tree` are not registered with the builder.
+    pub fn convert_to_webrender(&self,
+                                pipeline_id: PipelineId)
+                                -> webrender_traits::DisplayListBuilder {
+        let mut builder = webrender_traits::DisplayListBuilder::new(pipeline_id);
+        let mut scroll_root_to_clip_id = HashMap::new();
+        scroll_root_to_clip_id.insert(ScrollRootId::root(), builder.root_scroll_node_id());
+
+        for item in &self.list {
+            match *item {
+                DisplayItem::PushReferenceFrame(ref item) => {
+                    let reference_frame = &item.reference_frame;
+                    builder.push_reference_frame(reference_frame.transform,
+                                                 reference_frame.perspective,
+                                                 reference_frame.establishes_3d_context);
+                }
+                DisplayItem::PopReferenceFrame(_) => {
+                    builder.pop_reference_frame();
+                }
+                DisplayItem::PushStackingContext(ref item) => {
+                    let stacking_context = &item.stacking_context;
+                    builder.push_stacking_context(stacking_context.filters.clone(),
+                                                  stacking_context.blend_mode,
+                                                  stacking_context.opacity);
+
+                    if let Some(scroll_root_id) = stacking_context.established_scroll_root {
+                        if let Some(scroll_root) = self.clip_scroll_tree.get(&scroll_root_id) {
+                            let parent_clip_id = scroll_root.parent_id
+                                .and_then(|parent_id| scroll_root_to_clip_id.get(&parent_id).cloned())
+                                .unwrap_or_else(|| builder.root_scroll_node_id());
+                            let clip_id = builder.define_scroll_frame(parent_clip_id,
+                                                                      scroll_root.content_size,
+                                                                      scroll_root.clip);
+                            scroll_root_to_clip_id.insert(scroll_root_id, clip_id);
+                        }
+                    }
+                }
+                DisplayItem::PopStackingContext(_) => {
+                    builder.pop_stacking_context();
+                }
+                DisplayItem::SolidColor(ref item) => {
+                    builder.push_rect(&item.base.bounds, item.color);
+                }
+                DisplayItem::Text(ref item) => {
+                    builder.push_text(&item.base.bounds,
+                                      item.text_run.clone(),
+                                      item.range.clone(),
+                                      &item.baseline_origin,
+                                      item.text_color);
+                }
+                DisplayItem::Image(ref item) => {
+                    if let Some(key) = item.webrender_image.key {
+                        builder.push_image(&item.base.bounds,
+                                           &item.stretch_size,
+                                           &item.tile_spacing,
+                                           item.image_rendering.clone(),
+                                           key);
+                    }
+                }
+                DisplayItem::YuvImage(ref item) => {
+                    match item.layout {
+                        YuvPlanarLayout::Triplanar => {
+                            builder.push_yuv_image(&item.base.bounds,
+                                                   &item.stretch_size,
+                                                   item.y_data.clone(),
+                                                   item.u_data.clone(),
+                                                   item.v_data.clone()
+                                                       .expect("triplanar YUV image missing V plane"),
+                                                   item.y_size,
+                                                   item.color_space,
+                                                   item.color_range,
+                                                   item.color_depth);
+                        }
+                        YuvPlanarLayout::BiplanarInterleavedUV => {
+                            builder.push_nv12_image(&item.base.bounds,
+                                                    &item.stretch_size,
+                                                    item.y_data.clone(),
+                                                    item.u_data.clone(),
+                                                    item.y_size,
+                                                    item.color_space,
+                                                    item.color_range,
+                                                    item.color_depth);
+                        }
+                    }
+                }
+                DisplayItem::WebGL(ref item) => {
+                    builder.push_webgl_canvas(&item.base.bounds, item.context_id);
+                }
+                DisplayItem::Border(ref item) => {
+                    builder.push_border(&item.base.bounds,
+                                        &item.border_widths,
+                                        &item.color,
+                                        &item.style,
+                                        &item.radius,
+                                        &item.edge_clip,
+                                        &item.corner_clip);
+                }
+                DisplayItem::Gradient(ref item) => {
+                    builder.push_gradient(&item.base.bounds,
+                                          &item.start_point,
+                                          &item.end_point,
+                                          &item.stops);
+                }
+                DisplayItem::RadialGradient(ref item) => {
+                    builder.push_radial_gradient(&item.base.bounds,
+                                                 &item.center,
+                                                 &item.radius,
+                                                 &item.stops);
+                }
+                DisplayItem::ConicGradient(ref item) => {
+                    builder.push_conic_gradient(&item.base.bounds,
+                                                &item.center,
+                                                item.angle,
+                                                &item.stops);
+                }
+                DisplayItem::Line(ref item) => {
+                    builder.push_line(&item.base.bounds, item.color, item.style);
+                }
+                DisplayItem::BoxShadow(ref item) => {
+                    builder.push_box_shadow(&item.box_bounds,
+                                            &item.offset,
+                                            item.color,
+                                            item.blur_radius,
+                                            item.spread_radius,
+                                            item.border_radius,
+                                            item.clip_mode);
+                }
+                DisplayItem::Iframe(ref item) => {
+                    builder.push_iframe(&item.base.bounds, item.iframe);
+                }
+                DisplayItem::HitTest(_) => {}
             }
         }
 
-        self.hit_test_contents(traversal, &translated_point, client_point, scroll_offsets, result);
+        builder
     }
 
     pub fn print(&self) {
@@ -425,9 +1202,14 @@ impl DisplayList {
     pub fn print_with_tree(&self, print_tree: &mut PrintTree) {
         print_tree.new_level("Items".to_owned());
         for item in &self.list {
-            print_tree.add_item(format!("{:?} StackingContext: {:?}",
-                                        item,
-                                        item.base().stacking_context_id));
+            let stacking_context_id = match *item {
+                DisplayItem::PushStackingContext(ref item) => item.stacking_context.id,
+                DisplayItem::PopStackingContext(ref item) => item.stacking_context_id,
+                DisplayItem::PushReferenceFrame(ref item) => item.reference_frame.id,
+                DisplayItem::PopReferenceFrame(ref item) => item.reference_frame_id,
+                _ => item.base().unwrap().stacking_context_id,
+            };
+            print_tree.add_item(format!("{:?} StackingContext: {:?}", item, stacking_context_id));
         }
         print_tree.end_level();
     }
@@ -459,12 +1241,11 @@ impl<'a> DisplayListTraversal<'a> {
         debug_assert!(display_list.list.len() > start);
         debug_assert!(display_list.list.len() > end);
 
-        let stacking_context_start = display_list.list[0..start].iter().rposition(|item|
-            match item {
-                &DisplayItem::PushStackingContext(ref item) =>
-                    item.stacking_context.id == stacking_context_id,
-                _ => false,
-            }).unwrap_or(start);
+        // Look up the stacking context's `[start, end)` range in the index built once for this
+        // display list, instead of an O(n) backward scan over `display_list.list[0..start]`.
+        let stacking_context_start = display_list.index
+            .range_for_stacking_context(stacking_context_id)
+            .map_or(start, |(range_start, _)| range_start);
         debug_assert!(stacking_context_start <= start);
 
         DisplayListTraversal {
@@ -556,6 +1337,103 @@ pub enum StackingContextType {
     PseudoFloat,
 }
 
+/// Identifies one out-of-band animated value referenced by a `PropertyBinding`. The painting
+/// backend keeps a map from key to current value, updated per frame by a `DynamicProperties`
+/// message, and re-resolves bindings without the display list itself changing.
+#[derive(Clone, Copy, Eq, PartialEq, Hash, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct PropertyBindingKey(pub u64);
+
+/// A value that is either baked into the display list, or driven by an out-of-band
+/// animation/transition that the compositor updates per frame. Using this instead of a plain `T`
+/// lets `transform`, `perspective`, and animatable filter components stay in the display list
+/// while a CSS animation or transition on them runs without forcing a rebuild every frame.
+#[derive(Clone, Copy, HeapSizeOf, Deserialize, Serialize)]
+pub enum PropertyBinding<T> {
+    /// A value that won't change without a new display list.
+    Value(T),
+    /// A value driven by `key` in the backend's per-frame property table. The value here is
+    /// used until the first `DynamicProperties` update for `key` arrives.
+    Binding(PropertyBindingKey, T),
+}
+
+impl<T> PropertyBinding<T> {
+    /// This binding's current baked-in value, ignoring any live binding key. Non-WebRender
+    /// painting backends that don't track `DynamicProperties` fall back to this.
+    pub fn value(&self) -> &T {
+        match *self {
+            PropertyBinding::Value(ref value) => value,
+            PropertyBinding::Binding(_, ref value) => value,
+        }
+    }
+}
+
+impl<T> From<T> for PropertyBinding<T> {
+    fn from(value: T) -> PropertyBinding<T> {
+        PropertyBinding::Value(value)
+    }
+}
+
+/// One out-of-band update to an animated property, identified by the `PropertyBindingKey` its
+/// `PropertyBinding` was constructed with.
+#[derive(Clone, Deserialize, Serialize)]
+pub enum DynamicProperty {
+    Transform(PropertyBindingKey, Matrix4D<f32>),
+    Opacity(PropertyBindingKey, f32),
+    /// An update to a single animatable `Filter` parameter. `Filter`'s own variant already
+    /// disambiguates which CSS filter function a given key belongs to, so unlike `Transform` and
+    /// `Opacity` this one variant covers every animatable filter operation.
+    Filter(PropertyBindingKey, f32),
+}
+
+/// A batch of property updates sent to the painting backend once per animation frame, so the
+/// compositor can drive transitions and animations independently of layout.
+#[derive(Clone, Deserialize, Serialize)]
+pub struct DynamicProperties {
+    pub updates: Vec<DynamicProperty>,
+}
+
+/// A reference frame establishes a new coordinate system for its descendants, independently of
+/// whether those descendants also form a stacking context. Splitting this out of
+/// `StackingContext` makes it possible to express a transform that creates a new coordinate
+/// system without also forming a painting group, and vice versa.
+#[derive(Clone, Copy, HeapSizeOf, Deserialize, Serialize)]
+pub struct ReferenceFrame {
+    /// The ID of the stacking context that establishes this reference frame.
+    pub id: StackingContextId,
+
+    /// The transform to apply to this reference frame's descendants.
+    pub transform: PropertyBinding<Matrix4D<f32>>,
+
+    /// The perspective matrix to apply to descendants.
+    pub perspective: PropertyBinding<Matrix4D<f32>>,
+
+    /// Whether this reference frame establishes a new 3d rendering context.
+    pub establishes_3d_context: bool,
+}
+
+/// One operation in a CSS `filter` list. Every parameter that a CSS transition or animation can
+/// drive is wrapped in a `PropertyBinding`, the same way `StackingContext::opacity` is, so a
+/// running filter animation can update the painting backend's per-frame property table instead
+/// of forcing layout to rebuild the display list on every frame.
+///
+/// There's no `Opacity` variant here even though `opacity` is a valid `filter` function: a
+/// stacking context's opacity, whether it arrived via the `opacity` property or an `opacity()`
+/// filter function, is folded into `StackingContext::opacity` instead, since both the Azure and
+/// WebRender backends composite opacity once, as part of the stacking context itself, rather than
+/// as a per-operation step within the filter list.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub enum Filter {
+    Blur(Au),
+    Brightness(PropertyBinding<f32>),
+    Contrast(PropertyBinding<f32>),
+    Grayscale(PropertyBinding<f32>),
+    HueRotate(PropertyBinding<f32>),
+    Invert(PropertyBinding<f32>),
+    Saturate(PropertyBinding<f32>),
+    Sepia(PropertyBinding<f32>),
+    DropShadow(Point2D<Au>, Au, Color),
+}
+
 #[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
 /// Represents one CSS stacking context, which may or may not have a hardware layer.
 pub struct StackingContext {
@@ -571,23 +1449,24 @@ pub struct StackingContext {
     /// The overflow rect for this stacking context in its coordinate system.
     pub overflow: Rect<Au>,
 
+    /// The corner radii to apply when clipping to `overflow`, if this stacking context's overflow
+    /// area has `border-radius` set. When present, painting installs a rounded-rect mask instead
+    /// of a plain rectangular clip.
+    pub overflow_radii: Option<BorderRadii<Au>>,
+
     /// The `z-index` for this stacking context.
     pub z_index: i32,
 
-    /// CSS filters to be applied to this stacking context (including opacity).
-    pub filters: filter::T,
+    /// CSS filters to be applied to this stacking context, in application order.
+    pub filters: Vec<Filter>,
 
     /// The blend mode with which this stacking context blends with its backdrop.
     pub blend_mode: mix_blend_mode::T,
 
-    /// A transform to be applied to this stacking context.
-    pub transform: Matrix4D<f32>,
-
-    /// The perspective matrix to be applied to children.
-    pub perspective: Matrix4D<f32>,
-
-    /// Whether this stacking context creates a new 3d rendering context.
-    pub establishes_3d_context: bool,
+    /// This stacking context's opacity. Kept separate from `filters` (rather than folded into it
+    /// as an opacity filter function) so a CSS opacity transition can update it via a
+    /// `PropertyBinding` without touching the rest of the filter list.
+    pub opacity: PropertyBinding<f32>,
 
     /// The layer info for this stacking context, if there is any.
     pub layer_info: Option<LayerInfo>,
@@ -595,8 +1474,26 @@ pub struct StackingContext {
     /// Children of this StackingContext.
     pub children: Vec<StackingContext>,
 
-    /// If this StackingContext scrolls its overflow area, this will contain the id.
-    pub overflow_scroll_id: Option<StackingContextId>,
+    /// If this StackingContext scrolls its overflow area, this is the id of the scroll root it
+    /// establishes in the display list's `ClipScrollTree`.
+    pub established_scroll_root: Option<ScrollRootId>,
+
+    /// If this StackingContext's children should be transformed into a new coordinate system,
+    /// this is the reference frame it establishes. Kept separate from stacking/painting order
+    /// (`z_index`/`filters`/`blend_mode`) so a transform can create a new coordinate system
+    /// without also forming a painting group, and so a stacking context with no transform at all
+    /// doesn't need to carry identity matrices around.
+    ///
+    /// `DisplayList::generate_display_list` reads this field to emit a standalone
+    /// `PushReferenceFrameItem`/`PopReferenceFrameItem` pair, which is the sole source
+    /// `convert_to_webrender`, the Azure CPU painting path, and hit testing consult for this
+    /// stacking context's transform from then on. The one exception is
+    /// `overflow_rect_in_parent_space`, which reads this copy directly via
+    /// `reference_frame_transform`: it runs while the `StackingContext` tree is still being built
+    /// (before there's a flat display list to read a reference frame item out of), and again
+    /// during painting, when `intersects_rect_in_parent_context` tests a stacking context against
+    /// the tile rect.
+    pub established_reference_frame: Option<ReferenceFrame>,
 }
 
 impl StackingContext {
@@ -606,32 +1503,40 @@ impl StackingContext {
                context_type: StackingContextType,
                bounds: &Rect<Au>,
                overflow: &Rect<Au>,
+               overflow_radii: Option<BorderRadii<Au>>,
                z_index: i32,
-               filters: filter::T,
+               filters: Vec<Filter>,
                blend_mode: mix_blend_mode::T,
-               transform: Matrix4D<f32>,
-               perspective: Matrix4D<f32>,
-               establishes_3d_context: bool,
+               opacity: PropertyBinding<f32>,
                layer_info: Option<LayerInfo>,
-               scroll_id: Option<StackingContextId>)
+               established_scroll_root: Option<ScrollRootId>,
+               established_reference_frame: Option<ReferenceFrame>)
                -> StackingContext {
         StackingContext {
             id: id,
             context_type: context_type,
             bounds: *bounds,
             overflow: *overflow,
+            overflow_radii: overflow_radii,
             z_index: z_index,
             filters: filters,
             blend_mode: blend_mode,
-            transform: transform,
-            perspective: perspective,
-            establishes_3d_context: establishes_3d_context,
+            opacity: opacity,
             layer_info: layer_info,
             children: Vec::new(),
-            overflow_scroll_id: scroll_id,
+            established_scroll_root: established_scroll_root,
+            established_reference_frame: established_reference_frame,
         }
     }
 
+    /// The transform that this stacking context's reference frame applies to its descendants, or
+    /// the identity matrix if it doesn't establish one.
+    fn reference_frame_transform(&self) -> Matrix4D<f32> {
+        self.established_reference_frame
+            .as_ref()
+            .map_or_else(Matrix4D::identity, |frame| *frame.transform.value())
+    }
+
     pub fn add_child(&mut self, mut child: StackingContext) {
         child.update_overflow_for_all_children();
         self.children.push(child);
@@ -667,7 +1572,7 @@ impl StackingContext {
         let origin_y = self.bounds.origin.y.to_f32_px();
 
         let transform = Matrix4D::identity().pre_translated(origin_x, origin_y, 0.0)
-                                            .pre_mul(&self.transform);
+                                            .pre_mul(&self.reference_frame_transform());
         let transform_2d = transform.to_2d();
 
         let overflow = geometry::au_rect_to_f32_rect(self.overflow);
@@ -737,7 +1642,7 @@ impl fmt::Debug for StackingContext {
             "Pseudo-StackingContext"
         };
 
-        let scrollable_string = if self.overflow_scroll_id.is_some() {
+        let scrollable_string = if self.established_scroll_root.is_some() {
             " (scrolls overflow area)"
         } else {
             ""
@@ -758,14 +1663,20 @@ pub enum DisplayItem {
     SolidColor(Box<SolidColorDisplayItem>),
     Text(Box<TextDisplayItem>),
     Image(Box<ImageDisplayItem>),
+    YuvImage(Box<YuvImageDisplayItem>),
     WebGL(Box<WebGLDisplayItem>),
     Border(Box<BorderDisplayItem>),
     Gradient(Box<GradientDisplayItem>),
+    RadialGradient(Box<RadialGradientDisplayItem>),
+    ConicGradient(Box<ConicGradientDisplayItem>),
     Line(Box<LineDisplayItem>),
     BoxShadow(Box<BoxShadowDisplayItem>),
     Iframe(Box<IframeDisplayItem>),
+    HitTest(Box<HitTestDisplayItem>),
     PushStackingContext(Box<PushStackingContextItem>),
     PopStackingContext(Box<PopStackingContextItem>),
+    PushReferenceFrame(Box<PushReferenceFrameItem>),
+    PopReferenceFrame(Box<PopReferenceFrameItem>),
 }
 
 /// Information common to all display items.
@@ -777,37 +1688,41 @@ pub struct BaseDisplayItem {
     /// Metadata attached to this display item.
     pub metadata: DisplayItemMetadata,
 
-    /// The region to clip to.
-    pub clip: ClippingRegion,
-
     /// The section of the display list that this item belongs to.
     pub section: DisplayListSection,
 
     /// The id of the stacking context this item belongs to.
     pub stacking_context_id: StackingContextId,
+
+    /// The clip-scroll tree nodes that apply to this item: which scroll root's offset to apply
+    /// during hit testing, and which clip node's (and whose ancestors') region to apply during
+    /// painting and clip-based hit testing.
+    pub clip_and_scroll_info: ClipAndScrollInfo,
+
+    /// `bounds` intersected with the bounding rect of `clip_and_scroll_info`'s clip, computed
+    /// once by `DisplayList::cull_disjoint_items` after `clip_and_scroll_info` is resolvable
+    /// against a `ClipScrollTree`. Until then (and for items that end up with no `DisplayList` to
+    /// belong to) this is simply `bounds`. `intersects_rect_in_parent_context`, `hit_test`, and
+    /// `draw_into_context` read this instead of re-resolving the clip's bounding rect on every
+    /// call.
+    pub clipped_bounds: Rect<Au>,
 }
 
 impl BaseDisplayItem {
     #[inline(always)]
     pub fn new(bounds: &Rect<Au>,
                metadata: DisplayItemMetadata,
-               clip: &ClippingRegion,
                section: DisplayListSection,
-               stacking_context_id: StackingContextId)
+               stacking_context_id: StackingContextId,
+               clip_and_scroll_info: ClipAndScrollInfo)
                -> BaseDisplayItem {
-        // Detect useless clipping regions here and optimize them to `ClippingRegion::max()`.
-        // The painting backend may want to optimize out clipping regions and this makes it easier
-        // for it to do so.
         BaseDisplayItem {
             bounds: *bounds,
             metadata: metadata,
-            clip: if clip.does_not_clip_rect(&bounds) {
-                ClippingRegion::max()
-            } else {
-                (*clip).clone()
-            },
             section: section,
             stacking_context_id: stacking_context_id,
+            clip_and_scroll_info: clip_and_scroll_info,
+            clipped_bounds: *bounds,
         }
     }
 
@@ -819,9 +1734,10 @@ impl BaseDisplayItem {
                 node: OpaqueNode(0),
                 pointing: None,
             },
-            clip: ClippingRegion::max(),
             section: DisplayListSection::Content,
             stacking_context_id: StackingContextId::root(),
+            clip_and_scroll_info: ClipAndScrollInfo::simple(ScrollRootId::root()),
+            clipped_bounds: TypedRect::zero(),
         }
     }
 }
@@ -851,6 +1767,70 @@ pub struct ComplexClippingRegion {
     pub radii: BorderRadii<Au>,
 }
 
+impl ComplexClippingRegion {
+    /// Returns true if this rounded rectangle contains `point`.
+    fn contains_point(&self, point: &Point2D<Au>) -> bool {
+        self.radii.contains_point(&self.rect, point)
+    }
+
+    /// Returns true if this rounded rectangle might intersect `rect`. Like
+    /// `ClippingRegion::might_intersect_rect`, this can yield false positives but never false
+    /// negatives: if the overlap between `rect` and our own bounding rect is entirely cut off by
+    /// one of our corners, we can reject it outright; otherwise we assume an intersection.
+    fn might_intersect_rect(&self, rect: &Rect<Au>) -> bool {
+        let overlap = match self.rect.intersection(rect) {
+            Some(overlap) => overlap,
+            None => return false,
+        };
+        if self.radii.is_square() {
+            return true;
+        }
+
+        // The point of `overlap` closest to this corner's ellipse center is the one furthest
+        // from the corner it cuts off; if even that point falls outside the ellipse, none of
+        // `overlap` is in the painted region near this corner.
+        !self.excluded_by_top_left(&overlap) && !self.excluded_by_top_right(&overlap) &&
+            !self.excluded_by_bottom_left(&overlap) && !self.excluded_by_bottom_right(&overlap)
+    }
+
+    fn excluded_by_top_left(&self, overlap: &Rect<Au>) -> bool {
+        let center = Point2D::new(self.rect.origin.x + self.radii.top_left.width,
+                                  self.rect.origin.y + self.radii.top_left.height);
+        overlap.origin.x + overlap.size.width <= center.x &&
+            overlap.origin.y + overlap.size.height <= center.y &&
+            !self.contains_point(&Point2D::new(overlap.origin.x + overlap.size.width,
+                                               overlap.origin.y + overlap.size.height))
+    }
+
+    fn excluded_by_top_right(&self, overlap: &Rect<Au>) -> bool {
+        let right_x = self.rect.origin.x + self.rect.size.width;
+        let center = Point2D::new(right_x - self.radii.top_right.width,
+                                  self.rect.origin.y + self.radii.top_right.height);
+        overlap.origin.x >= center.x &&
+            overlap.origin.y + overlap.size.height <= center.y &&
+            !self.contains_point(&Point2D::new(overlap.origin.x,
+                                               overlap.origin.y + overlap.size.height))
+    }
+
+    fn excluded_by_bottom_left(&self, overlap: &Rect<Au>) -> bool {
+        let bottom_y = self.rect.origin.y + self.rect.size.height;
+        let center = Point2D::new(self.rect.origin.x + self.radii.bottom_left.width,
+                                  bottom_y - self.radii.bottom_left.height);
+        overlap.origin.x + overlap.size.width <= center.x && overlap.origin.y >= center.y &&
+            !self.contains_point(&Point2D::new(overlap.origin.x + overlap.size.width,
+                                               overlap.origin.y))
+    }
+
+    fn excluded_by_bottom_right(&self, overlap: &Rect<Au>) -> bool {
+        let right_x = self.rect.origin.x + self.rect.size.width;
+        let bottom_y = self.rect.origin.y + self.rect.size.height;
+        let center = Point2D::new(right_x - self.radii.bottom_right.width,
+                                  bottom_y - self.radii.bottom_right.height);
+        overlap.origin.x >= center.x && overlap.origin.y >= center.y &&
+            !self.contains_point(&Point2D::new(overlap.origin.x, overlap.origin.y))
+    }
+}
+
 impl ClippingRegion {
     /// Returns an empty clipping region that, if set, will result in no pixels being visible.
     #[inline]
@@ -895,12 +1875,12 @@ impl ClippingRegion {
         !self.main.is_empty()
     }
 
-    /// Returns true if this clipping region might contain the given point and false otherwise.
-    /// This is a quick, not a precise, test; it can yield false positives.
+    /// Returns true if this clipping region contains the given point, taking the rounded
+    /// corners of any complex regions into account.
     #[inline]
     pub fn might_intersect_point(&self, point: &Point2D<Au>) -> bool {
         self.main.contains(point) &&
-            self.complex.iter().all(|complex| complex.rect.contains(point))
+            self.complex.iter().all(|complex| complex.contains_point(point))
     }
 
     /// Returns true if this clipping region might intersect the given rectangle and false
@@ -911,6 +1891,15 @@ impl ClippingRegion {
             self.complex.iter().all(|complex| complex.rect.intersects(rect))
     }
 
+    /// Like `might_intersect_rect`, but also rejects `rect` when it falls entirely within a
+    /// complex region's cut-off corner, per `ComplexClippingRegion::might_intersect_rect`. Still
+    /// a conservative test: it can yield false positives, but never false negatives.
+    #[inline]
+    pub fn might_intersect_rounded_rect(&self, rect: &Rect<Au>) -> bool {
+        self.main.intersects(rect) &&
+            self.complex.iter().all(|complex| complex.might_intersect_rect(rect))
+    }
+
     /// Returns true if this clipping region completely surrounds the given rect.
     #[inline]
     pub fn does_not_clip_rect(&self, rect: &Rect<Au>) -> bool {
@@ -978,6 +1967,17 @@ impl ClippingRegion {
     pub fn is_max(&self) -> bool {
         self.main == max_rect() && self.complex.is_empty()
     }
+
+    /// Returns a new clipping region that clips to both this region and `other`, used to combine
+    /// a clip node's own region with its ancestors' while walking the clip-scroll tree.
+    pub fn intersect(&self, other: &ClippingRegion) -> ClippingRegion {
+        let mut result = self.clone();
+        result.intersect_rect(&other.main);
+        for complex in &other.complex {
+            result.intersect_with_rounded_rect(&complex.rect, &complex.radii);
+        }
+        result
+    }
 }
 
 impl fmt::Debug for ClippingRegion {
@@ -994,6 +1994,60 @@ impl fmt::Debug for ClippingRegion {
     }
 }
 
+/// A clip region on the paint-time clip stack. This is distinct from `ClippingRegion`, which is
+/// the *resolved*, per-item clip baked into the display list: `ClipRegion` is what
+/// `draw_with_state` pushes when entering a stacking context or scroll root and pops when
+/// leaving, intersecting with whatever was already on the stack as it goes, so nested overflow
+/// containers (each potentially with their own `border-radius`) clip correctly.
+#[derive(Clone, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct ClipRegion {
+    /// The main rectangular region, already intersected with every enclosing clip on the stack.
+    pub main: Rect<Au>,
+    /// The corner radii of the innermost region on the stack, if that region is rounded. A plain
+    /// rectangular clip is installed only when this is `None` and `complex` is empty.
+    pub radii: Option<BorderRadii<Au>>,
+    /// Additional rounded-rect regions from enclosing clips that can't be folded into `radii`
+    /// alone (e.g. two differently-rounded ancestors whose rects don't nest).
+    pub complex: Vec<ComplexClippingRegion>,
+}
+
+impl ClipRegion {
+    /// Returns a clip region that clips no pixels out. Used as the initial state at the root of
+    /// the display list.
+    pub fn max() -> ClipRegion {
+        ClipRegion {
+            main: max_rect(),
+            radii: None,
+            complex: Vec::new(),
+        }
+    }
+
+    /// Returns true if painting needs to install a rounded-rect mask for this region rather than
+    /// a plain rectangular clip.
+    pub fn has_rounded_corners(&self) -> bool {
+        self.radii.is_some() || !self.complex.is_empty()
+    }
+
+    /// Intersects this clip region (the region already in effect) with a region belonging to a
+    /// stacking context or scroll root being entered, folding any outer rounding into `complex`
+    /// so it keeps clipping once a new, possibly unrounded, region is pushed on top.
+    pub fn intersect(&self, other: &ClipRegion) -> ClipRegion {
+        let main = self.main.intersection(&other.main).unwrap_or(Rect::zero());
+
+        let mut complex = self.complex.clone();
+        if let Some(radii) = self.radii {
+            complex.push(ComplexClippingRegion { rect: self.main, radii: radii });
+        }
+        complex.extend(other.complex.iter().cloned());
+
+        ClipRegion {
+            main: main,
+            radii: other.radii,
+            complex: complex,
+        }
+    }
+}
+
 impl ComplexClippingRegion {
     // TODO(pcwalton): This could be more aggressive by considering points that touch the inside of
     // the border radius ellipse.
@@ -1022,6 +2076,21 @@ pub struct DisplayItemMetadata {
     pub pointing: Option<Cursor>,
 }
 
+impl DisplayItemMetadata {
+    /// Condenses this metadata into the compound tag `hit_test` hands back to callers: the DOM
+    /// node identifier plus a 16-bit cursor selector, so the constellation/compositor can
+    /// route clicks and update the cursor from one value without a second style query.
+    fn to_tag(&self) -> ItemTag {
+        ItemTag(self.node.0 as u64, self.pointing.map_or(0, |cursor| cursor as u16))
+    }
+}
+
+/// A compound tag returned by hit testing: `ItemTag(node_id, cursor)`, where `node_id` identifies
+/// the originating DOM node and
+/// `cursor` is the `u16` encoding of the `cursor` property to show while hovering this item.
+#[derive(Clone, Copy, PartialEq, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct ItemTag(pub u64, pub u16);
+
 /// Paints a solid color.
 #[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
 pub struct SolidColorDisplayItem {
@@ -1089,6 +2158,88 @@ pub struct ImageDisplayItem {
     pub image_rendering: image_rendering::T,
 }
 
+/// How the plane buffers backing a `YuvImageDisplayItem` are laid out.
+#[derive(Clone, Copy, Eq, PartialEq, HeapSizeOf, Deserialize, Serialize)]
+pub enum YuvPlanarLayout {
+    /// Three fully-planar buffers: Y, U, and V, each its own allocation.
+    Triplanar,
+    /// The Y plane in its own buffer, with U and V interleaved together in a second buffer, as
+    /// produced by NV12 and similar formats.
+    BiplanarInterleavedUV,
+}
+
+/// The color space coefficients used to convert a YUV image's samples to RGB at paint time.
+#[derive(Clone, Copy, Eq, PartialEq, HeapSizeOf, Deserialize, Serialize)]
+pub enum YuvColorSpace {
+    Rec601,
+    Rec709,
+    Rec2020,
+}
+
+/// Whether a YUV image's samples span the full 0-255 range or the "limited" range (16-235 for
+/// luma, 16-240 for chroma) used by most video codecs.
+#[derive(Clone, Copy, Eq, PartialEq, HeapSizeOf, Deserialize, Serialize)]
+pub enum YuvColorRange {
+    Limited,
+    Full,
+}
+
+/// The number of bits each YUV sample occupies, and therefore how the plane buffers pack them.
+/// 10- and 12-bit content is typically delivered by the decoder as two bytes per sample (with the
+/// extra high bits unused), rather than tightly packed, so this is needed alongside `y_size` to
+/// know how to stride through a plane.
+#[derive(Clone, Copy, Eq, PartialEq, HeapSizeOf, Deserialize, Serialize)]
+pub enum ColorDepth {
+    Eight,
+    Ten,
+    Twelve,
+}
+
+/// Paints a YUV video frame directly from its native plane buffers. Keeping the frame in its
+/// native plane format avoids a full-frame CPU conversion to RGBA on every frame; the backend
+/// performs the YCbCr-to-RGB conversion at paint time using the coefficient matrix for
+/// `color_space`, after undoing `color_range`'s luma/chroma offsets.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct YuvImageDisplayItem {
+    pub base: BaseDisplayItem,
+
+    /// The Y (luma) plane.
+    #[ignore_heap_size_of = "Because it is non-owning"]
+    pub y_data: Arc<IpcSharedMemory>,
+
+    /// The U (Cb) plane. When `layout` is `BiplanarInterleavedUV`, this buffer holds U and V
+    /// interleaved together and `v_data` is `None`.
+    #[ignore_heap_size_of = "Because it is non-owning"]
+    pub u_data: Arc<IpcSharedMemory>,
+
+    /// The V (Cr) plane, or `None` when `layout` is `BiplanarInterleavedUV` and V is interleaved
+    /// into `u_data` instead.
+    #[ignore_heap_size_of = "Because it is non-owning"]
+    pub v_data: Option<Arc<IpcSharedMemory>>,
+
+    /// How the plane buffers above are laid out.
+    pub layout: YuvPlanarLayout,
+
+    /// The dimensions of the Y plane, in pixels. The chroma planes are assumed to be
+    /// half-resolution in each dimension (4:2:0 subsampling), matching what video decoders
+    /// commonly hand back.
+    pub y_size: Size2D<i32>,
+
+    /// The color space used to convert samples to RGB.
+    pub color_space: YuvColorSpace,
+
+    /// Whether the samples use the full or limited value range.
+    pub color_range: YuvColorRange,
+
+    /// The number of bits each sample occupies.
+    pub color_depth: ColorDepth,
+
+    /// The dimensions to which the image display item should be stretched. If this is smaller
+    /// than the bounds of this display item, then the image will be repeated in the appropriate
+    /// direction to tile the entire bounds.
+    pub stretch_size: Size2D<Au>,
+}
+
 #[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
 pub struct WebGLDisplayItem {
     pub base: BaseDisplayItem,
@@ -1104,6 +2255,18 @@ pub struct IframeDisplayItem {
     pub iframe: PipelineId,
 }
 
+/// A clip-bounded hit-test region that carries no paint content of its own. Layout emits one of
+/// these wherever an element should respond to pointer events, instead of setting
+/// `DisplayItemMetadata::pointing` on whichever visual item happens to paint there. This lets a
+/// region be hittable without being painted (a transparent click target) or painted without being
+/// hittable (decorative fills). `hit_test` treats it like any other item: every item whose
+/// `metadata.pointing` is set, visual items included, is still tested.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct HitTestDisplayItem {
+    /// Fields common to all display items.
+    pub base: BaseDisplayItem,
+}
+
 /// Paints a gradient.
 #[derive(Clone, Deserialize, HeapSizeOf, Serialize)]
 pub struct GradientDisplayItem {
@@ -1120,6 +2283,42 @@ pub struct GradientDisplayItem {
     pub stops: Vec<GradientStop>,
 }
 
+/// Paints a radial gradient, as created by the CSS `radial-gradient()` image function.
+#[derive(Clone, Deserialize, HeapSizeOf, Serialize)]
+pub struct RadialGradientDisplayItem {
+    /// Fields common to all display items.
+    pub base: BaseDisplayItem,
+
+    /// The center of the gradient.
+    pub center: Point2D<Au>,
+
+    /// The radius of the gradient along each axis. Storing both axes (rather than a single
+    /// radius) lets this represent elliptical gradients as well as circular ones.
+    pub radius: Size2D<Au>,
+
+    /// A list of color stops, pre-resolved to their final positions at display-list-construction
+    /// time, matching `GradientDisplayItem`'s `stops` so both stay serializable the same way.
+    pub stops: Vec<GradientStop>,
+}
+
+/// Paints a conic gradient, as created by the CSS `conic-gradient()` image function.
+#[derive(Clone, Deserialize, HeapSizeOf, Serialize)]
+pub struct ConicGradientDisplayItem {
+    /// Fields common to all display items.
+    pub base: BaseDisplayItem,
+
+    /// The center of the gradient.
+    pub center: Point2D<Au>,
+
+    /// The angle, in radians, that the first stop is rotated to from straight up.
+    pub angle: f32,
+
+    /// A list of color stops, keyed on the normalized angle around `center` (`atan2(p.y -
+    /// center.y, p.x - center.x)` mapped into `[0, 1)`), pre-resolved at
+    /// display-list-construction time.
+    pub stops: Vec<GradientStop>,
+}
+
 /// Paints a border.
 #[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
 pub struct BorderDisplayItem {
@@ -1139,6 +2338,91 @@ pub struct BorderDisplayItem {
     ///
     /// TODO(pcwalton): Elliptical radii.
     pub radius: BorderRadii<Au>,
+
+    /// Per-edge dash/dot clip geometry, precomputed at display-list-construction time from
+    /// `border_widths` and `style` so the backend never has to guess dash placement. `None` for
+    /// an edge whose style isn't `dashed` or `dotted`, which is painted as a solid stroke.
+    pub edge_clip: SideOffsets2D<Option<BorderClip>>,
+
+    /// Per-corner dash/dot clip geometry. Kept separate from `edge_clip` because a rounded
+    /// corner is clipped by subtracting two tangent-line SDFs rather than by tiling, but its
+    /// `period` is derived from the same edges so dashes stay continuous across the
+    /// corner-to-edge boundary.
+    pub corner_clip: BorderRadii<Option<BorderClip>>,
+}
+
+/// How a border edge or corner is clipped into dashes or dots when its `border-style` is
+/// `dashed` or `dotted`.
+#[derive(Clone, Copy, PartialEq, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub enum BorderClipKind {
+    /// Tile a straight edge into `period`-long tiles, painting the first quarter and the last
+    /// quarter of each tile (`in_dash = pos < period / 4 || pos > 3 * period / 4`) and skipping
+    /// the middle half. Adjacent tiles' painted quarters join at the tile boundary, so the
+    /// visible dash is `period / 2` long, separated by a `period / 2` gap.
+    DashEdge,
+    /// Clip a rounded corner's arc into dashes by subtracting two tangent-line SDFs, one from
+    /// each edge the corner joins, so the corner's dashes line up with `DashEdge` tiling on
+    /// either side of it.
+    DashCorner,
+    /// Tile an edge or corner into circular dots of radius `radius`, spaced `period` apart.
+    Dot,
+}
+
+/// The precomputed dash/dot geometry for one border edge or corner.
+#[derive(Clone, Copy, PartialEq, Debug, HeapSizeOf, Deserialize, Serialize)]
+pub struct BorderClip {
+    /// How this edge or corner is clipped.
+    pub kind: BorderClipKind,
+
+    /// The length of one tile along a straight edge, or the corresponding arc length around a
+    /// corner.
+    pub period: Au,
+
+    /// The radius of an individual dot. Zero for `DashEdge` and `DashCorner`.
+    pub radius: Au,
+}
+
+impl BorderClip {
+    /// Computes the dash/dot geometry for a border edge of the given `style` and `width`, or
+    /// `None` if `style` is painted as a solid stroke.
+    pub fn for_style(style: border_style::T, width: Au) -> Option<BorderClip> {
+        match style {
+            border_style::T::dashed => {
+                Some(BorderClip {
+                    kind: BorderClipKind::DashEdge,
+                    period: Au::from_f32_px(width.to_f32_px() * 4.0),
+                    radius: Au(0),
+                })
+            }
+            border_style::T::dotted => {
+                Some(BorderClip {
+                    kind: BorderClipKind::Dot,
+                    period: Au::from_f32_px(width.to_f32_px() * 2.0),
+                    radius: Au::from_f32_px(width.to_f32_px() / 2.0),
+                })
+            }
+            _ => None,
+        }
+    }
+
+    /// Derives a corner's dash/dot geometry from the `BorderClip`s of the two edges it joins,
+    /// taking `period` and `radius` from `first_edge` if it is dashed or dotted and from
+    /// `second_edge` otherwise; when both are, `second_edge` is not consulted. Returns `None` if
+    /// neither adjoining edge is dashed or dotted.
+    pub fn for_corner(first_edge: Option<BorderClip>,
+                      second_edge: Option<BorderClip>)
+                      -> Option<BorderClip> {
+        match (first_edge, second_edge) {
+            (Some(edge), _) | (None, Some(edge)) => {
+                let kind = match edge.kind {
+                    BorderClipKind::DashEdge => BorderClipKind::DashCorner,
+                    kind => kind,
+                };
+                Some(BorderClip { kind: kind, period: edge.period, radius: edge.radius })
+            }
+            (None, None) => None,
+        }
+    }
 }
 
 /// Information about the border radii.
@@ -1182,6 +2466,63 @@ impl BorderRadii<Au> {
     pub fn scale_corner_by(corner: Size2D<Au>, s: f32) -> Size2D<Au> {
         Size2D::new(corner.width.scale_by(s), corner.height.scale_by(s))
     }
+
+    /// Returns true if the rounded rectangle described by `rect` and `self` contains `point`.
+    /// Rejects anything outside the plain `rect` first; then, for each corner whose radii are
+    /// nonzero, treats the corner as an ellipse centered `(rx, ry)` in from that corner and
+    /// rejects points in the corner's quadrant that fall outside it. A radius larger than half
+    /// the rect's width or height is clamped to that half first. Points in the central cross
+    /// between corners, and corners with a zero radius, fall back to the plain rect check above.
+    pub fn contains_point(&self, rect: &Rect<Au>, point: &Point2D<Au>) -> bool {
+        if !rect.contains(point) {
+            return false;
+        }
+
+        fn corner_contains(point: &Point2D<Au>,
+                           center: Point2D<Au>,
+                           radii: Size2D<Au>,
+                           in_quadrant: bool)
+                           -> bool {
+            if !in_quadrant || radii.width <= Au(0) || radii.height <= Au(0) {
+                return true;
+            }
+            let dx = (point.x - center.x).to_f32_px() / radii.width.to_f32_px();
+            let dy = (point.y - center.y).to_f32_px() / radii.height.to_f32_px();
+            dx * dx + dy * dy <= 1.0
+        }
+
+        let half_width = rect.size.width / 2;
+        let half_height = rect.size.height / 2;
+        let clamp = |radii: Size2D<Au>| {
+            Size2D::new(cmp::min(radii.width, half_width), cmp::min(radii.height, half_height))
+        };
+
+        let top_left = clamp(self.top_left);
+        let top_right = clamp(self.top_right);
+        let bottom_left = clamp(self.bottom_left);
+        let bottom_right = clamp(self.bottom_right);
+
+        let left_x = rect.origin.x;
+        let right_x = rect.origin.x + rect.size.width;
+        let top_y = rect.origin.y;
+        let bottom_y = rect.origin.y + rect.size.height;
+
+        let top_left_center = Point2D::new(left_x + top_left.width, top_y + top_left.height);
+        let top_right_center = Point2D::new(right_x - top_right.width, top_y + top_right.height);
+        let bottom_left_center =
+            Point2D::new(left_x + bottom_left.width, bottom_y - bottom_left.height);
+        let bottom_right_center =
+            Point2D::new(right_x - bottom_right.width, bottom_y - bottom_right.height);
+
+        corner_contains(point, top_left_center, top_left,
+                       point.x < top_left_center.x && point.y < top_left_center.y) &&
+        corner_contains(point, top_right_center, top_right,
+                       point.x > top_right_center.x && point.y < top_right_center.y) &&
+        corner_contains(point, bottom_left_center, bottom_left,
+                       point.x < bottom_left_center.x && point.y > bottom_left_center.y) &&
+        corner_contains(point, bottom_right_center, bottom_right,
+                       point.x > bottom_right_center.x && point.y > bottom_right_center.y)
+    }
 }
 
 impl<T> BorderRadii<T> where T: PartialEq + Zero {
@@ -1238,33 +2579,53 @@ pub struct BoxShadowDisplayItem {
     /// The spread radius of this shadow.
     pub spread_radius: Au,
 
-    /// The border radius of this shadow.
-    ///
-    /// TODO(pcwalton): Elliptical radii; different radii for each corner.
-    pub border_radius: Au,
+    /// The border radii of this shadow's rounded-rect mask, one `Size2D<Au>` per corner so an
+    /// element with mixed or elliptical `border-radius` casts a shadow with matching corners.
+    pub border_radius: BorderRadii<Au>,
 
     /// How we should clip the result.
     pub clip_mode: BoxShadowClipMode,
 }
 
 /// Defines a stacking context.
+///
+/// Any reference frame this context establishes is emitted as a standalone
+/// `PushReferenceFrameItem`/`PopReferenceFrameItem` pair wrapping this item, so that
+/// `convert_to_webrender`, the Azure CPU painting path, and hit testing all read the transform off
+/// that flat item instead of off `StackingContext` itself. This item's own `stacking_context`
+/// is the whole `StackingContext`, so it still holds a copy of that reference frame in
+/// `established_reference_frame`; of this item's consumers, only tile culling (via
+/// `overflow_rect_in_parent_space`) reads it.
+///
+/// Unlike the visual/hittable display items, this carries no `BaseDisplayItem`: it never paints,
+/// never hit-tests, and never clips on its own, so there's no bounds or clip-and-scroll info for
+/// one to hold. `stacking_context` already carries the bounds and overflow this item's consumers
+/// need.
 #[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
 pub struct PushStackingContextItem {
-    /// Fields common to all display items.
-    pub base: BaseDisplayItem,
-
     pub stacking_context: StackingContext,
 }
 
-/// Defines a stacking context.
+/// Defines a stacking context. Carries no `BaseDisplayItem`; see `PushStackingContextItem`.
 #[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
 pub struct PopStackingContextItem {
-    /// Fields common to all display items.
-    pub base: BaseDisplayItem,
-
     pub stacking_context_id: StackingContextId,
 }
 
+/// Establishes a reference frame, independently of whatever stacking context it's nested in.
+/// Carries no `BaseDisplayItem`; see `PushStackingContextItem`.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct PushReferenceFrameItem {
+    pub reference_frame: ReferenceFrame,
+}
+
+/// Ends a reference frame previously established by a `PushReferenceFrameItem` with the same id.
+/// Carries no `BaseDisplayItem`; see `PushStackingContextItem`.
+#[derive(Clone, HeapSizeOf, Deserialize, Serialize)]
+pub struct PopReferenceFrameItem {
+    pub reference_frame_id: StackingContextId,
+}
+
 
 /// How a box shadow should be clipped.
 #[derive(Clone, Copy, Debug, PartialEq, HeapSizeOf, Deserialize, Serialize)]
@@ -1281,11 +2642,23 @@ pub enum BoxShadowClipMode {
 
 impl DisplayItem {
     /// Paints this display item into the given painting context.
-    fn draw_into_context(&self, paint_context: &mut PaintContext) {
-        let this_clip = &self.base().clip;
-        match paint_context.transient_clip {
-            Some(ref transient_clip) if transient_clip == this_clip => {}
-            Some(_) | None => paint_context.push_transient_clip((*this_clip).clone()),
+    fn draw_into_context(&self, paint_context: &mut PaintContext, clip_scroll_tree: &ClipScrollTree) {
+        // Control items carry no clip-and-scroll info of their own to resolve, and paint nothing
+        // below regardless.
+        if let Some(base) = self.base() {
+            let this_clip = clip_scroll_tree.resolved_clip(base.clip_and_scroll_info.clip_node_id);
+            // `clipped_bounds == bounds` only says this item's bounds sit inside the clip's
+            // *bounding rect*; a rounded clip node can still cut into those bounds well within
+            // that rect, so the fast path is only sound once the clip is known to be rectangular.
+            let this_clip = if base.clipped_bounds == base.bounds && this_clip.complex.is_empty() {
+                ClippingRegion::max()
+            } else {
+                this_clip
+            };
+            match paint_context.transient_clip {
+                Some(ref transient_clip) if *transient_clip == this_clip => {}
+                Some(_) | None => paint_context.push_transient_clip(this_clip),
+            }
         }
 
         match *self {
@@ -1313,6 +2686,21 @@ impl DisplayItem {
                     image_item.image_rendering.clone());
             }
 
+            DisplayItem::YuvImage(ref image_item) => {
+                debug!("Drawing YUV image at {:?}.", image_item.base.bounds);
+                paint_context.draw_yuv_image(
+                    &image_item.base.bounds,
+                    &image_item.stretch_size,
+                    &image_item.y_data,
+                    &image_item.u_data,
+                    image_item.v_data.as_ref(),
+                    image_item.layout,
+                    image_item.y_size,
+                    image_item.color_space,
+                    image_item.color_range,
+                    image_item.color_depth);
+            }
+
             DisplayItem::WebGL(_) => {
                 panic!("Shouldn't be here, WebGL display items are created just with webrender");
             }
@@ -1332,6 +2720,20 @@ impl DisplayItem {
                                                    &gradient.stops);
             }
 
+            DisplayItem::RadialGradient(ref gradient) => {
+                paint_context.draw_radial_gradient(&gradient.base.bounds,
+                                                   &gradient.center,
+                                                   &gradient.radius,
+                                                   &gradient.stops);
+            }
+
+            DisplayItem::ConicGradient(ref gradient) => {
+                paint_context.draw_conic_gradient(&gradient.base.bounds,
+                                                  &gradient.center,
+                                                  gradient.angle,
+                                                  &gradient.stops);
+            }
+
             DisplayItem::Line(ref line) => {
                 paint_context.draw_line(&line.base.bounds, line.color, line.style)
             }
@@ -1347,51 +2749,223 @@ impl DisplayItem {
 
             DisplayItem::Iframe(..) => {}
 
+            DisplayItem::HitTest(..) => {}
+
             DisplayItem::PushStackingContext(..) => {}
 
             DisplayItem::PopStackingContext(..) => {}
+
+            DisplayItem::PushReferenceFrame(..) => {}
+
+            DisplayItem::PopReferenceFrame(..) => {}
         }
     }
 
-    pub fn intersects_rect_in_parent_context(&self, rect: Option<Rect<Au>>) -> bool {
+    pub fn intersects_rect_in_parent_context(&self,
+                                             rect: Option<Rect<Au>>,
+                                             clip_scroll_tree: &ClipScrollTree)
+                                             -> bool {
         let rect = match rect {
             Some(ref rect) => rect,
             None => return true,
         };
 
-        if !rect.intersects(&self.bounds()) {
+        if !rect.intersects(&self.clipped_bounds()) {
             return false;
         }
 
-        self.base().clip.might_intersect_rect(&rect)
+        let base = self.base().expect("control items aren't checked against tile bounds");
+        let clip = clip_scroll_tree.resolved_clip(base.clip_and_scroll_info.clip_node_id);
+        clip.might_intersect_rounded_rect(&rect)
+    }
+
+    /// This item's `BaseDisplayItem`, or `None` for a control item (`PushStackingContext`,
+    /// `PopStackingContext`, `PushReferenceFrame`, `PopReferenceFrame`), which carries no bounds,
+    /// clip, or metadata of its own since it never paints, hit-tests, or clips.
+    pub fn base(&self) -> Option<&BaseDisplayItem> {
+        match *self {
+            DisplayItem::SolidColor(ref solid_color) => Some(&solid_color.base),
+            DisplayItem::Text(ref text) => Some(&text.base),
+            DisplayItem::Image(ref image_item) => Some(&image_item.base),
+            DisplayItem::YuvImage(ref image_item) => Some(&image_item.base),
+            DisplayItem::WebGL(ref webgl_item) => Some(&webgl_item.base),
+            DisplayItem::Border(ref border) => Some(&border.base),
+            DisplayItem::Gradient(ref gradient) => Some(&gradient.base),
+            DisplayItem::RadialGradient(ref gradient) => Some(&gradient.base),
+            DisplayItem::ConicGradient(ref gradient) => Some(&gradient.base),
+            DisplayItem::Line(ref line) => Some(&line.base),
+            DisplayItem::BoxShadow(ref box_shadow) => Some(&box_shadow.base),
+            DisplayItem::Iframe(ref iframe) => Some(&iframe.base),
+            DisplayItem::HitTest(ref hit_test) => Some(&hit_test.base),
+            DisplayItem::PushStackingContext(_) |
+            DisplayItem::PopStackingContext(_) |
+            DisplayItem::PushReferenceFrame(_) |
+            DisplayItem::PopReferenceFrame(_) => None,
+        }
     }
 
-    pub fn base(&self) -> &BaseDisplayItem {
+    /// Mutable counterpart of `base`, used by `DisplayList::cull_disjoint_items` to fill in
+    /// `clipped_bounds` once it's known.
+    fn base_mut(&mut self) -> Option<&mut BaseDisplayItem> {
         match *self {
-            DisplayItem::SolidColor(ref solid_color) => &solid_color.base,
-            DisplayItem::Text(ref text) => &text.base,
-            DisplayItem::Image(ref image_item) => &image_item.base,
-            DisplayItem::WebGL(ref webgl_item) => &webgl_item.base,
-            DisplayItem::Border(ref border) => &border.base,
-            DisplayItem::Gradient(ref gradient) => &gradient.base,
-            DisplayItem::Line(ref line) => &line.base,
-            DisplayItem::BoxShadow(ref box_shadow) => &box_shadow.base,
-            DisplayItem::Iframe(ref iframe) => &iframe.base,
-            DisplayItem::PushStackingContext(ref stacking_context) => &stacking_context.base,
-            DisplayItem::PopStackingContext(ref item) => &item.base,
+            DisplayItem::SolidColor(ref mut solid_color) => Some(&mut solid_color.base),
+            DisplayItem::Text(ref mut text) => Some(&mut text.base),
+            DisplayItem::Image(ref mut image_item) => Some(&mut image_item.base),
+            DisplayItem::YuvImage(ref mut image_item) => Some(&mut image_item.base),
+            DisplayItem::WebGL(ref mut webgl_item) => Some(&mut webgl_item.base),
+            DisplayItem::Border(ref mut border) => Some(&mut border.base),
+            DisplayItem::Gradient(ref mut gradient) => Some(&mut gradient.base),
+            DisplayItem::RadialGradient(ref mut gradient) => Some(&mut gradient.base),
+            DisplayItem::ConicGradient(ref mut gradient) => Some(&mut gradient.base),
+            DisplayItem::Line(ref mut line) => Some(&mut line.base),
+            DisplayItem::BoxShadow(ref mut box_shadow) => Some(&mut box_shadow.base),
+            DisplayItem::Iframe(ref mut iframe) => Some(&mut iframe.base),
+            DisplayItem::HitTest(ref mut hit_test) => Some(&mut hit_test.base),
+            DisplayItem::PushStackingContext(_) |
+            DisplayItem::PopStackingContext(_) |
+            DisplayItem::PushReferenceFrame(_) |
+            DisplayItem::PopReferenceFrame(_) => None,
         }
     }
 
+    /// The id of the stacking context that contains this item. Only meaningful for items that
+    /// carry a `BaseDisplayItem`; control items are matched explicitly wherever their own
+    /// (more precise) stacking context id is needed instead.
     pub fn stacking_context_id(&self) -> StackingContextId {
-        self.base().stacking_context_id
+        self.base().expect("control items don't have their own stacking_context_id").stacking_context_id
     }
 
+    /// Which paint-order section this item belongs to. Only meaningful for items that carry a
+    /// `BaseDisplayItem`; control items are never sorted by section, since they're emitted
+    /// directly by `generate_display_list` rather than bucketed with the rest of a stacking
+    /// context's children.
     pub fn section(&self) -> DisplayListSection {
-        self.base().section
+        self.base().expect("control items don't have their own section").section
     }
 
     pub fn bounds(&self) -> Rect<Au> {
-        self.base().bounds
+        self.base().map_or_else(TypedRect::zero, |base| base.bounds)
+    }
+
+    /// This item's `bounds`, intersected with its own clip's bounding rect. See
+    /// `BaseDisplayItem::clipped_bounds`.
+    pub fn clipped_bounds(&self) -> Rect<Au> {
+        self.base().map_or_else(TypedRect::zero, |base| base.clipped_bounds)
+    }
+
+    /// Returns a copy of this item translated by `delta`, used when repositioning a run of items
+    /// relative to a synthesized layer's own origin instead of their parent stacking context's.
+    fn translated(&self, delta: &Point2D<Au>) -> DisplayItem {
+        fn translated_base(base: &BaseDisplayItem, delta: &Point2D<Au>) -> BaseDisplayItem {
+            BaseDisplayItem {
+                bounds: base.bounds.translate(delta),
+                clipped_bounds: base.clipped_bounds.translate(delta),
+                ..base.clone()
+            }
+        }
+
+        match *self {
+            DisplayItem::SolidColor(ref item) => {
+                DisplayItem::SolidColor(Box::new(SolidColorDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::Text(ref item) => {
+                DisplayItem::Text(Box::new(TextDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    baseline_origin: item.baseline_origin + *delta,
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::Image(ref item) => {
+                DisplayItem::Image(Box::new(ImageDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::YuvImage(ref item) => {
+                DisplayItem::YuvImage(Box::new(YuvImageDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::WebGL(ref item) => {
+                DisplayItem::WebGL(Box::new(WebGLDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::Border(ref item) => {
+                DisplayItem::Border(Box::new(BorderDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::Gradient(ref item) => {
+                DisplayItem::Gradient(Box::new(GradientDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    start_point: item.start_point + *delta,
+                    end_point: item.end_point + *delta,
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::RadialGradient(ref item) => {
+                DisplayItem::RadialGradient(Box::new(RadialGradientDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    center: item.center + *delta,
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::ConicGradient(ref item) => {
+                DisplayItem::ConicGradient(Box::new(ConicGradientDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    center: item.center + *delta,
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::Line(ref item) => {
+                DisplayItem::Line(Box::new(LineDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::BoxShadow(ref item) => {
+                DisplayItem::BoxShadow(Box::new(BoxShadowDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    box_bounds: item.box_bounds.translate(delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::Iframe(ref item) => {
+                DisplayItem::Iframe(Box::new(IframeDisplayItem {
+                    base: translated_base(&item.base, delta),
+                    ..(**item).clone()
+                }))
+            }
+            DisplayItem::HitTest(ref item) => {
+                DisplayItem::HitTest(Box::new(HitTestDisplayItem {
+                    base: translated_base(&item.base, delta),
+                }))
+            }
+            DisplayItem::PushStackingContext(ref item) => {
+                let mut stacking_context = item.stacking_context.clone();
+                stacking_context.bounds = stacking_context.bounds.translate(delta);
+                stacking_context.overflow = stacking_context.overflow.translate(delta);
+                DisplayItem::PushStackingContext(Box::new(PushStackingContextItem {
+                    stacking_context: stacking_context,
+                }))
+            }
+            DisplayItem::PopStackingContext(ref item) => {
+                DisplayItem::PopStackingContext(Box::new((**item).clone()))
+            }
+            DisplayItem::PushReferenceFrame(ref item) => {
+                DisplayItem::PushReferenceFrame(Box::new((**item).clone()))
+            }
+            DisplayItem::PopReferenceFrame(ref item) => {
+                DisplayItem::PopReferenceFrame(Box::new((**item).clone()))
+            }
+        }
     }
 
     pub fn debug_with_level(&self, level: u32) {
@@ -1402,17 +2976,19 @@ impl DisplayItem {
         println!("{}+ {:?}", indent, self);
     }
 
-    fn hit_test(&self, point: Point2D<Au>) -> Option<DisplayItemMetadata> {
-        // TODO(pcwalton): Use a precise algorithm here. This will allow us to properly hit
-        // test elements with `border-radius`, for example.
-        let base_item = self.base();
+    fn hit_test(&self, point: Point2D<Au>, clip_scroll_tree: &ClipScrollTree) -> Option<ItemTag> {
+        // `ClippingRegion::might_intersect_point`, below, uses `BorderRadii::contains_point` to
+        // reject points clipped out by a `border-radius`ed overflow ancestor. `DisplayItem::Border`
+        // further rejects points in its own rounded-rect cut-off corners below.
+        let base_item = self.base().expect("control items are never hit-tested directly");
 
-        if !base_item.clip.might_intersect_point(&point) {
-            // Clipped out.
+        if !base_item.clipped_bounds.contains(&point) {
+            // Outside `bounds`, or outside the bounding rect of the clip: can't possibly hit.
             return None;
         }
-        if !self.bounds().contains(&point) {
-            // Can't possibly hit.
+        let clip = clip_scroll_tree.resolved_clip(base_item.clip_and_scroll_info.clip_node_id);
+        if !clip.might_intersect_point(&point) {
+            // Clipped out by a rounded corner that `clipped_bounds`, a plain rect, can't capture.
             return None;
         }
         if base_item.metadata.pointing.is_none() {
@@ -1422,6 +2998,12 @@ impl DisplayItem {
 
         match *self {
             DisplayItem::Border(ref border) => {
+                if !border.radius.is_square() &&
+                        !border.radius.contains_point(&border.base.bounds, &point) {
+                    // Outside the rounded outer edge of the border box.
+                    return None;
+                }
+
                 // If the point is inside the border, it didn't hit the border!
                 let interior_rect =
                     Rect::new(
@@ -1446,7 +3028,7 @@ impl DisplayItem {
             _ => {}
         }
 
-        Some(base_item.metadata)
+        Some(base_item.metadata.to_tag())
     }
 }
 
@@ -1460,6 +3042,14 @@ impl fmt::Debug for DisplayItem {
             return write!(f, "PopStackingContext({:?}", item.stacking_context_id);
         }
 
+        if let DisplayItem::PushReferenceFrame(ref item) = *self {
+            return write!(f, "PushReferenceFrame({:?})", item.reference_frame.id);
+        }
+
+        if let DisplayItem::PopReferenceFrame(ref item) = *self {
+            return write!(f, "PopReferenceFrame({:?})", item.reference_frame_id);
+        }
+
         write!(f, "{} @ {:?} {:?}",
             match *self {
                 DisplayItem::SolidColor(ref solid_color) =>
@@ -1470,17 +3060,23 @@ impl fmt::Debug for DisplayItem {
                             solid_color.color.a),
                 DisplayItem::Text(_) => "Text".to_owned(),
                 DisplayItem::Image(_) => "Image".to_owned(),
+                DisplayItem::YuvImage(_) => "YuvImage".to_owned(),
                 DisplayItem::WebGL(_) => "WebGL".to_owned(),
                 DisplayItem::Border(_) => "Border".to_owned(),
                 DisplayItem::Gradient(_) => "Gradient".to_owned(),
+                DisplayItem::RadialGradient(_) => "RadialGradient".to_owned(),
+                DisplayItem::ConicGradient(_) => "ConicGradient".to_owned(),
                 DisplayItem::Line(_) => "Line".to_owned(),
                 DisplayItem::BoxShadow(_) => "BoxShadow".to_owned(),
                 DisplayItem::Iframe(_) => "Iframe".to_owned(),
+                DisplayItem::HitTest(_) => "HitTest".to_owned(),
                 DisplayItem::PushStackingContext(_) => "".to_owned(),
                 DisplayItem::PopStackingContext(_) => "".to_owned(),
+                DisplayItem::PushReferenceFrame(_) => "".to_owned(),
+                DisplayItem::PopReferenceFrame(_) => "".to_owned(),
             },
             self.bounds(),
-            self.base().clip
+            self.base().unwrap().clip_and_scroll_info
         )
     }
 }
@@ -1506,8 +3102,10 @@ impl WebRenderImageInfo {
     }
 }
 
-/// The type of the scroll offset list. This is only populated if WebRender is in use.
-pub type ScrollOffsetMap = HashMap<StackingContextId, Point2D<f32>>;
+/// The type of the scroll offset list, keyed by `ScrollRootId` rather than `StackingContextId` so
+/// that several scrollable areas nesting inside one stacking context are tracked independently.
+/// This is only populated if WebRender is in use.
+pub type ScrollOffsetMap = HashMap<ScrollRootId, Point2D<f32>>;
 
 
 pub trait SimpleMatrixDetection {